@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Card {
+	pub suit: Suit,
+	pub rank: Rank,
+}
+
+impl Card {
+	pub fn new(suit: Suit, rank: Rank) -> Card {
+		return Card{suit, rank}
+	}
+
+	pub fn colour(&self) -> Colour {
+		return match self.suit {
+			Suit::Diamonds | Suit::Hearts => Colour::Red,
+			Suit::Clubs | Suit::Spades => Colour::Black,
+		}
+	}
+
+	// Returns an array slice containing all the cards in a standard 52-card deck
+	pub fn all_cards() -> &'static [Card] {
+		static CARDS: [Card; 52] = [
+			Card { suit: Suit::Spades, rank: Rank::Two },
+			Card { suit: Suit::Spades, rank: Rank::Three },
+			Card { suit: Suit::Spades, rank: Rank::Four },
+			Card { suit: Suit::Spades, rank: Rank::Five },
+			Card { suit: Suit::Spades, rank: Rank::Six },
+			Card { suit: Suit::Spades, rank: Rank::Seven },
+			Card { suit: Suit::Spades, rank: Rank::Eight },
+			Card { suit: Suit::Spades, rank: Rank::Nine },
+			Card { suit: Suit::Spades, rank: Rank::Ten },
+			Card { suit: Suit::Spades, rank: Rank::Jack },
+			Card { suit: Suit::Spades, rank: Rank::Queen },
+			Card { suit: Suit::Spades, rank: Rank::King },
+			Card { suit: Suit::Spades, rank: Rank::Ace },
+			Card { suit: Suit::Hearts, rank: Rank::Two },
+			Card { suit: Suit::Hearts, rank: Rank::Three },
+			Card { suit: Suit::Hearts, rank: Rank::Four },
+			Card { suit: Suit::Hearts, rank: Rank::Five },
+			Card { suit: Suit::Hearts, rank: Rank::Six },
+			Card { suit: Suit::Hearts, rank: Rank::Seven },
+			Card { suit: Suit::Hearts, rank: Rank::Eight },
+			Card { suit: Suit::Hearts, rank: Rank::Nine },
+			Card { suit: Suit::Hearts, rank: Rank::Ten },
+			Card { suit: Suit::Hearts, rank: Rank::Jack },
+			Card { suit: Suit::Hearts, rank: Rank::Queen },
+			Card { suit: Suit::Hearts, rank: Rank::King },
+			Card { suit: Suit::Hearts, rank: Rank::Ace },
+			Card { suit: Suit::Diamonds, rank: Rank::Two },
+			Card { suit: Suit::Diamonds, rank: Rank::Three },
+			Card { suit: Suit::Diamonds, rank: Rank::Four },
+			Card { suit: Suit::Diamonds, rank: Rank::Five },
+			Card { suit: Suit::Diamonds, rank: Rank::Six },
+			Card { suit: Suit::Diamonds, rank: Rank::Seven },
+			Card { suit: Suit::Diamonds, rank: Rank::Eight },
+			Card { suit: Suit::Diamonds, rank: Rank::Nine },
+			Card { suit: Suit::Diamonds, rank: Rank::Ten },
+			Card { suit: Suit::Diamonds, rank: Rank::Jack },
+			Card { suit: Suit::Diamonds, rank: Rank::Queen },
+			Card { suit: Suit::Diamonds, rank: Rank::King },
+			Card { suit: Suit::Diamonds, rank: Rank::Ace },
+			Card { suit: Suit::Clubs, rank: Rank::Two },
+			Card { suit: Suit::Clubs, rank: Rank::Three },
+			Card { suit: Suit::Clubs, rank: Rank::Four },
+			Card { suit: Suit::Clubs, rank: Rank::Five },
+			Card { suit: Suit::Clubs, rank: Rank::Six },
+			Card { suit: Suit::Clubs, rank: Rank::Seven },
+			Card { suit: Suit::Clubs, rank: Rank::Eight },
+			Card { suit: Suit::Clubs, rank: Rank::Nine },
+			Card { suit: Suit::Clubs, rank: Rank::Ten },
+			Card { suit: Suit::Clubs, rank: Rank::Jack },
+			Card { suit: Suit::Clubs, rank: Rank::Queen },
+			Card { suit: Suit::Clubs, rank: Rank::King },
+			Card { suit: Suit::Clubs, rank: Rank::Ace }
+		];
+		&CARDS
+	}
+
+	// returns true if self can stack on top of other in a pile, eg. if self is 2D and other is 3S.
+	pub fn can_pile_onto(&self, other:Card) -> bool {
+		self.colour() != other.colour() && other.rank.index() - self.rank.index() == 1
+	}
+
+	// returns true if self can stack on top of other in a foundation stack, eg. if self is 2D and other is AD.
+	pub fn can_stack_onto_in_foundation(&self, other:Card) -> bool {
+		self.suit == other.suit && self.rank.index() - other.rank.index() == 1
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Colour {
+	Red,
+	Black,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Copy, Serialize, Deserialize)]
+pub enum Suit {
+	Diamonds,
+	Clubs,
+	Hearts,
+	Spades,
+}
+
+impl Suit {
+	pub fn all() -> &'static [Suit] {
+		static SUITS: [Suit; 4] = [
+			Suit::Diamonds,
+			Suit::Clubs,
+			Suit::Hearts,
+			Suit::Spades,
+		];
+		&SUITS
+	}
+
+	// the position of this suit's foundation, counting from the leftmost foundation.
+	pub fn foundation_index(&self) -> usize {
+		return match self {
+			Suit::Diamonds => 0,
+			Suit::Clubs => 1,
+			Suit::Hearts => 2,
+			Suit::Spades => 3
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Rank {
+	Ace,
+	Two,
+	Three,
+	Four,
+	Five,
+	Six,
+	Seven,
+	Eight,
+	Nine,
+	Ten,
+	Jack,
+	Queen,
+	King,
+}
+
+impl Rank {
+	pub fn from_index(i: i8) -> Option<Rank> {
+		return match i {
+			0 => Some(Rank::Ace),
+			1 => Some(Rank::Two),
+			2 => Some(Rank::Three),
+			3 => Some(Rank::Four),
+			4 => Some(Rank::Five),
+			5 => Some(Rank::Six),
+			6 => Some(Rank::Seven),
+			7 => Some(Rank::Eight),
+			8 => Some(Rank::Nine),
+			9 => Some(Rank::Ten),
+			10 => Some(Rank::Jack),
+			11 => Some(Rank::Queen),
+			12 => Some(Rank::King),
+			_ => None
+		}
+	}
+
+	pub fn letter(&self) -> &str {
+		return match self {
+			Rank::Ace => "A",
+			Rank::Two => "2",
+			Rank::Three => "3",
+			Rank::Four => "4",
+			Rank::Five => "5",
+			Rank::Six => "6",
+			Rank::Seven => "7",
+			Rank::Eight => "8",
+			Rank::Nine => "9",
+			Rank::Ten => "10",
+			Rank::Jack => "J",
+			Rank::Queen => "Q",
+			Rank::King => "K",
+		}
+	}
+
+	pub fn index(&self) -> i8 {
+		return match self {
+			Rank::Ace => 0,
+			Rank::Two => 1,
+			Rank::Three => 2,
+			Rank::Four => 3,
+			Rank::Five => 4,
+			Rank::Six => 5,
+			Rank::Seven => 6,
+			Rank::Eight => 7,
+			Rank::Nine => 8,
+			Rank::Ten => 9,
+			Rank::Jack => 10,
+			Rank::Queen => 11,
+			Rank::King => 12,
+		}
+	}
+
+	// returns the predecessor of the current rank.
+	// eg. pred(King) == Some(Queen), pred(Ace) = None.
+	pub fn pred(&self) -> Option<Rank> {
+		let prev_index = self.index() - 1;
+		Rank::from_index(prev_index)
+	}
+}
@@ -0,0 +1,152 @@
+//! The bouncing card cascade that celebrates a win.
+//!
+//! Cards leave the foundations one at a time, highest rank first, and bounce along the bottom of the
+//! screen until they go off the side. The simulation runs in fixed time steps with its own seeded
+//! random numbers, so it plays out exactly the same way at any frame rate, and can be stepped and
+//! checked without drawing anything. Positions are in the frontend's screen coordinates: x grows to
+//! the right, y grows downwards, and a card's position is its top-left corner.
+
+use crate::card::{Card, Rank, Suit};
+use crate::rng::Rng;
+use std::collections::VecDeque;
+
+pub const CASCADE_STEPS_PER_SEC: u32 = 120;
+const STEP: f32 = 1. / CASCADE_STEPS_PER_SEC as f32;
+const GRAVITY: f32 = 1500.; // px/s^2
+const BOUNCE: f32 = 0.75; // the fraction of a card's vertical speed that's kept when it bounces
+const MIN_SPEED_X: f32 = 80.; // px/s
+const MAX_SPEED_X: f32 = 320.;
+const MAX_LAUNCH_SPEED_Y: f32 = 400.; // the fastest a card can be thrown upwards as it leaves its foundation
+const LAUNCH_INTERVAL: u32 = CASCADE_STEPS_PER_SEC / 5; // steps between cards leaving the foundations
+const TRAIL_INTERVAL: u32 = 4; // steps between the positions that are kept for a card's trail
+const TRAIL_LEN: usize = 60; // the number of trail positions kept per card
+
+// the size of the screen and where everything is on it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CascadeLayout {
+	pub width: f32,
+	pub height: f32,
+	pub card_w: f32,
+	pub card_h: f32,
+	pub foundations: [(f32, f32); 4], // the top-left corner of each foundation, indexed by Suit::foundation_index
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CascadeCard {
+	pub card: Card,
+	pub x: f32,
+	pub y: f32,
+	pub vx: f32,
+	pub vy: f32,
+	pub trail: VecDeque<(f32, f32)>, // where the card has been recently, oldest first
+	pub is_gone: bool, // true once the card has left the screen
+}
+
+pub struct Cascade {
+	layout: CascadeLayout,
+	rng: Rng,
+	to_launch: Vec<Card>, // the cards still waiting on the foundations. the next card to leave is last
+	cards: Vec<CascadeCard>, // the cards that have left the foundations, in the order they left
+	steps: u64, // the number of steps simulated so far
+	elapsed: f64, // seconds of real time passed to `update` so far
+}
+
+impl Cascade {
+	// starts a cascade from four full foundations
+	pub fn new(layout: CascadeLayout, seed: u64) -> Cascade {
+		// kings leave first, going along the foundations from left to right, then queens and so on
+		let mut suits = Suit::all().to_vec();
+		suits.sort_by_key(|suit| suit.foundation_index());
+		let mut to_launch = Vec::new();
+		for rank_index in 0..13 {
+			for suit in suits.iter().rev() {
+				to_launch.push(Card::new(*suit, Rank::from_index(rank_index).unwrap()));
+			}
+		}
+		return Cascade { layout, rng: Rng::new(seed), to_launch, cards: Vec::new(), steps: 0, elapsed: 0. }
+	}
+
+	pub fn cards(&self) -> &[CascadeCard] {
+		return &self.cards
+	}
+
+	pub fn steps(&self) -> u64 {
+		return self.steps
+	}
+
+	// the highest card still waiting to leave the given suit's foundation, if any
+	pub fn foundation_top(&self, suit: Suit) -> Option<Rank> {
+		return self.to_launch.iter().rev().find(|card| card.suit == suit).map(|card| card.rank)
+	}
+
+	// returns true once every card has left the screen
+	pub fn is_finished(&self) -> bool {
+		return self.to_launch.is_empty() && self.cards.iter().all(|card| card.is_gone)
+	}
+
+	// advances the simulation by `dt` seconds of real time.
+	// however `dt` is split up, the same total always ends up at the same step.
+	pub fn update(&mut self, dt: f64) {
+		self.elapsed += dt;
+		// the small nudge stops rounding errors in `elapsed` from losing a step right on a boundary
+		let target_steps = (self.elapsed * CASCADE_STEPS_PER_SEC as f64 + 1e-6).floor() as u64;
+		while self.steps < target_steps && !self.is_finished() {
+			self.step();
+		}
+	}
+
+	// runs the simulation until every card has left the screen
+	pub fn finish(&mut self) {
+		while !self.is_finished() {
+			self.step();
+		}
+	}
+
+	// advances the simulation by a single fixed time step
+	pub fn step(&mut self) {
+		if self.steps % LAUNCH_INTERVAL as u64 == 0 {
+			self.launch_next();
+		}
+
+		let layout = self.layout;
+		let record_trail = self.steps % TRAIL_INTERVAL as u64 == 0;
+		for c in self.cards.iter_mut().filter(|c| !c.is_gone) {
+			c.vy += GRAVITY * STEP;
+			c.x += c.vx * STEP;
+			c.y += c.vy * STEP;
+
+			let floor = layout.height - layout.card_h;
+			if c.y > floor {
+				c.y = floor;
+				c.vy = -c.vy * BOUNCE;
+			}
+
+			if c.x + layout.card_w < 0. || c.x > layout.width {
+				c.is_gone = true;
+			} else if record_trail {
+				c.trail.push_back((c.x, c.y));
+				if c.trail.len() > TRAIL_LEN {
+					c.trail.pop_front();
+				}
+			}
+		}
+		self.steps += 1;
+	}
+
+	fn launch_next(&mut self) {
+		let card = match self.to_launch.pop() {
+			Some(card) => card,
+			None => return,
+		};
+		let (x, y) = self.layout.foundations[card.suit.foundation_index()];
+		let direction = if self.rng.gen_below(2) == 0 { -1. } else { 1. };
+		let vx = direction * (MIN_SPEED_X + self.random_fraction() * (MAX_SPEED_X - MIN_SPEED_X));
+		let vy = -self.random_fraction() * MAX_LAUNCH_SPEED_Y;
+		self.cards.push(CascadeCard { card, x, y, vx, vy, trail: VecDeque::new(), is_gone: false });
+	}
+
+	// returns a random number in 0..1
+	fn random_fraction(&mut self) -> f32 {
+		return (self.rng.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+	}
+}
@@ -0,0 +1,768 @@
+use serde::{Deserialize, Serialize};
+use crate::card::{Card, Rank, Suit};
+use crate::pile::Pile;
+use crate::rng::Rng;
+use crate::score::Scoring;
+use crate::solver::is_pointless;
+use crate::strategy::Greedy;
+use std::collections::HashMap;
+
+pub const N_PILES: u8 = 7; // number of piles
+
+// the options that can vary between games of klondike
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct Rules {
+	pub draw_count: DrawCount,
+	pub pass_limit: PassLimit,
+	pub scoring: Scoring,
+}
+
+// how many cards are turned from the stock onto the waste at a time
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum DrawCount {
+	#[default]
+	One,
+	Three,
+}
+
+impl DrawCount {
+	pub fn n_cards(&self) -> usize {
+		return match self {
+			DrawCount::One => 1,
+			DrawCount::Three => 3,
+		}
+	}
+}
+
+// how many times the player may go through the stock, eg. Vegas rules only allow one pass
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum PassLimit {
+	#[default]
+	Unlimited,
+	Passes(u32), // the total number of passes allowed, including the first one
+}
+
+impl PassLimit {
+	// returns true if the player may start another pass, having already started `passes` passes
+	pub fn allows_another_pass(&self, passes:u32) -> bool {
+		return match self {
+			PassLimit::Unlimited => true,
+			PassLimit::Passes(limit) => passes < *limit,
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+	pub rules: Rules,
+	pub stock: Vec<Card>, // face down. The top of the stock (ie. the next card to be drawn) is the last element
+	pub waste: Vec<Card>, // face up. The top of the waste (ie. the playable card) is the last element
+	pub passes: u32, // the number of passes through the stock so far, including the current one
+	pub piles: Vec<Pile>,
+	pub foundation_fill_levels: HashMap<Suit, Rank>,
+	#[serde(skip)] // this is only a click in progress, so it isn't worth saving
+	pub move_in_progress: Option<MoveInProgress>,
+	pub history: Vec<HistoryEntry>, // the moves made so far, oldest first, with what undo and replays need to know about each
+	pub redo_stack: Vec<Move>, // moves that have been undone, with the most recently undone last
+	pub seed: Option<u64>, // the deal number, if this game was dealt from one
+	pub elapsed: f64, // seconds spent playing this game, counted from the first move
+	pub moves: u32, // the number of moves the player has made, including redone moves
+	pub undos: u32, // the number of moves the player has undone
+	pub points: i32, // the score from the moves made so far, before any adjustments for time
+	pub carried_score: i32, // the Vegas balance carried over from earlier games
+	pub stats_recorded: bool, // true once this game's result has been added to the statistics
+	pub replay_saved: bool, // true once a replay of this game has been written
+}
+
+impl Game {
+	// deals a new game from the given deal number. The same seed always produces the same deal,
+	// on every platform, so deal numbers can be shared between players.
+	pub fn from_seed(seed: u64, rules: Rules) -> Game {
+		let mut cards = Card::all_cards().to_vec();
+		Rng::new(seed).shuffle(&mut cards);
+
+		let mut game = Game::new(cards, rules);
+		game.seed = Some(seed);
+		return game;
+	}
+
+	// deals a new game from the given deck, which should contain all 52 cards.
+	// the deck is dealt from the front, so the caller is responsible for shuffling it first.
+	pub fn new(deck: Vec<Card>, rules: Rules) -> Game {
+		let mut deck = deck.into_iter();
+		let mut game = Game::empty(rules);
+
+		for pile_size in 1..=N_PILES {
+			let mut pile = Pile::new();
+			for i in 0..pile_size {
+				let card = deck.next().unwrap();
+				if i == pile_size - 1 {
+					pile.visible.push(card);
+				} else {
+					pile.hidden.push(card);
+				}
+			}
+			game.piles.push(pile);
+		}
+
+		// the rest of the deck goes into the stock, so that it's drawn in deck order
+		game.stock = deck.rev().collect();
+
+		return game;
+	}
+
+	// the same game again from its first position, as it was before any moves were made. This works
+	// for games loaded from a position as well as ones dealt from a deal number.
+	pub fn restarted(&self) -> Game {
+		let mut start = self.clone();
+		while !start.history.is_empty() {
+			start.undo_move();
+		}
+		let mut game = Game::empty(self.rules);
+		game.stock = start.stock;
+		game.waste = start.waste;
+		game.passes = start.passes;
+		game.piles = start.piles;
+		game.foundation_fill_levels = start.foundation_fill_levels;
+		game.seed = self.seed;
+		return game
+	}
+
+	// a game with no cards anywhere, and no piles
+	pub(crate) fn empty(rules: Rules) -> Game {
+		return Game {
+			rules,
+			stock: Vec::new(),
+			waste: Vec::new(),
+			passes: 1,
+			piles: Vec::new(),
+			foundation_fill_levels: HashMap::new(),
+			move_in_progress: None,
+			history: Vec::new(),
+			redo_stack: Vec::new(),
+			seed: None,
+			elapsed: 0.,
+			moves: 0,
+			undos: 0,
+			points: rules.scoring.initial_points(),
+			carried_score: 0,
+			stats_recorded: false,
+			replay_saved: false,
+		}
+	}
+
+	pub fn foundation_top_card(&self, suit:Suit) -> Option<Card> {
+		let rank = self.foundation_fill_levels.get(&suit)?;
+		Some(Card::new(suit, *rank))
+	}
+
+	// every move that can be made in the current position
+	pub fn legal_moves(&self) -> Vec<Move> {
+		let mut moves = Vec::new();
+		let mut sources = vec![Source::Stock, Source::Waste];
+		sources.extend(Suit::all().iter().map(|suit| Source::Foundation(*suit)));
+		for (pile_index, pile) in self.piles.iter().enumerate() {
+			sources.extend((0..pile.visible.len()).map(|target_card_index| Source::Pile{ pile_index, target_card_index }));
+		}
+		for source in sources {
+			self.push_moves_from(source, &mut moves);
+		}
+		return moves
+	}
+
+	// the legal moves for whatever's under the mouse. Returns None if there aren't any.
+	pub fn calc_moves(&self, target:MouseTarget) -> Option<Vec<Move>> {
+		let mut moves = Vec::new();
+		match target {
+			MouseTarget::StockDeck | MouseTarget::Recycle => {
+				self.push_moves_from(Source::Stock, &mut moves);
+				moves.retain(|mv| match target {
+					MouseTarget::StockDeck => matches!(mv, Move::DrawStock{..}),
+					_ => matches!(mv, Move::RecycleWaste),
+				});
+			}
+			MouseTarget::StockTop => self.push_moves_from(Source::Waste, &mut moves),
+			MouseTarget::Foundation(suit) => self.push_moves_from(Source::Foundation(suit), &mut moves),
+			MouseTarget::EmptyPile(_) => {} // impossible
+			MouseTarget::PileCard{ pile_index, target_card_index, .. } => self.push_moves_from(Source::Pile{ pile_index, target_card_index }, &mut moves),
+		}
+
+		if moves.is_empty() {
+			None
+		} else {
+			Some(moves)
+		}
+	}
+
+	// adds every legal move of the cards at the given source
+	fn push_moves_from(&self, source:Source, moves:&mut Vec<Move>) {
+		match source {
+			Source::Stock => {
+				if !self.stock.is_empty() {
+					let n_cards = self.rules.draw_count.n_cards().min(self.stock.len()) as u8;
+					moves.push(Move::DrawStock{ n_cards });
+				}
+				if self.can_recycle() {
+					moves.push(Move::RecycleWaste);
+				}
+			}
+			Source::Waste => {
+				if let Some(card) = self.waste.last().copied() {
+					if self.fits_on_foundation(card) {
+						moves.push(Move::CardMove{ card, src: MoveSrc::FromStock, dest: MoveDest::ToFoundation(card.suit) });
+					}
+					for pile_index in 0..self.piles.len() {
+						if self.fits_on_pile(card, pile_index) {
+							moves.push(Move::CardMove{ card, src: MoveSrc::FromStock, dest: MoveDest::ToPile(pile_index) });
+						}
+					}
+				}
+			}
+			Source::Foundation(suit) => {
+				if let Some(card) = self.foundation_top_card(suit) {
+					for pile_index in 0..self.piles.len() {
+						if self.fits_on_pile(card, pile_index) {
+							moves.push(Move::CardMove{ card, src: MoveSrc::FromFoundation(suit), dest: MoveDest::ToPile(pile_index) });
+						}
+					}
+				}
+			}
+			Source::Pile{ pile_index, target_card_index } => {
+				let pile = match self.piles.get(pile_index) {
+					Some(pile) => pile,
+					None => return,
+				};
+				let card = match pile.visible.get(target_card_index) {
+					Some(card) => *card,
+					None => return,
+				};
+				let n_cards = (pile.visible.len() - target_card_index) as u8;
+				let reveals_hidden_card = target_card_index == 0 && !pile.hidden.is_empty();
+
+				// only single cards can go to the foundations
+				let mut dests = Vec::new();
+				if n_cards == 1 && self.fits_on_foundation(card) {
+					dests.push(MoveDest::ToFoundation(card.suit));
+				}
+				for dest_pile_index in 0..self.piles.len() {
+					if dest_pile_index != pile_index && self.fits_on_pile(card, dest_pile_index) {
+						dests.push(MoveDest::ToPile(dest_pile_index));
+					}
+				}
+				for dest in dests {
+					moves.push(Move::CardMove{
+						card,
+						src: MoveSrc::FromPile{
+							pile_index,
+							n_cards,
+							progress_value: self.calc_progress_value_for_pile_card_move(pile_index, target_card_index, reveals_hidden_card, dest),
+							reveals_hidden_card,
+							target_card_index,
+						},
+						dest,
+					});
+				}
+			}
+		}
+	}
+
+	// returns true if the card could go on its foundation
+	fn fits_on_foundation(&self, card:Card) -> bool {
+		return match self.foundation_top_card(card.suit) {
+			Some(top) => card.can_stack_onto_in_foundation(top),
+			None => card.rank == Rank::Ace,
+		}
+	}
+
+	// returns true if the card could go on top of the given pile
+	fn fits_on_pile(&self, card:Card, pile_index:usize) -> bool {
+		let pile = &self.piles[pile_index];
+		return match pile.top_card() {
+			Some(top) => card.can_pile_onto(top),
+			None => pile.is_empty() && card.rank == Rank::King,
+		}
+	}
+
+	fn calc_progress_value_for_pile_card_move(&self, pile_index:usize, target_card_index:usize, reveals_hidden_card:bool, dest:MoveDest) -> usize {
+		if target_card_index == 0 {
+			if reveals_hidden_card { return 2 }
+
+			else if self.piles[pile_index].len() == 1 {
+				return match dest {
+					MoveDest::ToFoundation(_) => 1,
+					MoveDest::ToPile(dest_pile_index) => {
+						// if it's from a single-card pile to non-empty pile, that's progress.
+						if !self.piles[dest_pile_index].is_empty() {
+							1
+						} else { // otherwise it's just shifting between two empty piles.
+							0
+						}
+					}
+				}
+			}
+		}
+		
+		return 0
+	}
+
+	// true if the move is one of the legal moves in this position
+	pub fn is_legal_move(&self, mv:Move) -> bool {
+		let source = match mv {
+			Move::DrawStock{..} | Move::RecycleWaste => Source::Stock,
+			Move::CardMove{ src: MoveSrc::FromStock, .. } => Source::Waste,
+			Move::CardMove{ src: MoveSrc::FromFoundation(suit), .. } => Source::Foundation(suit),
+			Move::CardMove{ src: MoveSrc::FromPile{ pile_index, target_card_index, .. }, .. } => Source::Pile{ pile_index, target_card_index },
+		};
+		let mut moves = Vec::new();
+		self.push_moves_from(source, &mut moves);
+		return moves.contains(&mv)
+	}
+
+	// makes a new move. Any undone moves can no longer be redone. Returns false (and leaves the game
+	// unchanged) if the move isn't legal.
+	pub fn exec_move(&mut self, mv:Move) -> bool {
+		if !self.is_legal_move(mv) {
+			return false
+		}
+		let applied = self.apply_move(mv);
+		if applied {
+			self.redo_stack.clear();
+			self.moves += 1;
+			self.debug_validate(mv);
+		}
+		return applied
+	}
+
+	// returns false (and leaves the game unchanged) if the move isn't possible
+	fn apply_move(&mut self, mv:Move) -> bool {
+		let entry = HistoryEntry { mv, points: self.points, time: self.elapsed, position_hash: self.position_hash() };
+		let applied = self.move_cards(mv);
+		if applied {
+			self.history.push(entry);
+			self.score_move(mv);
+		}
+		return applied
+	}
+
+	fn move_cards(&mut self, mv:Move) -> bool {
+		match mv {
+			Move::CardMove{ card, src, dest } => {
+				match src {
+					MoveSrc::FromStock => {
+						if let Some(card) = self.waste.pop() {
+							match dest {
+								MoveDest::ToPile(pile_index) => {
+									self.piles[pile_index].visible.push(card);
+								}
+								MoveDest::ToFoundation(suit) => {
+									self.foundation_fill_levels.insert(suit, card.rank);
+								}
+							}
+							return true
+						}
+					}
+					MoveSrc::FromFoundation(suit) => {
+						match dest {
+							MoveDest::ToPile(pile_index) => {
+								if let Some(top_card) = self.pop_foundation(suit) {
+									self.piles[pile_index].visible.push(top_card);
+									return true
+								}
+							}
+							MoveDest::ToFoundation(_) => {} // impossible
+						}
+					}
+					MoveSrc::FromPile{ pile_index, target_card_index, .. } => {
+						let pile = &mut self.piles[pile_index];
+						let removed:Vec<Card> = pile.visible.drain(target_card_index..).collect();
+						if pile.visible.is_empty() {
+							if let Some(next) = pile.hidden.pop() {
+								pile.visible.push(next);
+							}
+						}
+						match dest {
+							MoveDest::ToPile(dest_pile_index) => {
+								for card in removed {
+									self.piles[dest_pile_index].visible.push(card);
+								}
+							}
+							MoveDest::ToFoundation(suit) => {
+								self.foundation_fill_levels.insert(suit, card.rank);
+							}
+						}
+						return true
+					}
+				}
+			}
+			Move::DrawStock{ n_cards } => {
+				let n_cards = n_cards as usize;
+				if n_cards > 0 && n_cards == self.rules.draw_count.n_cards().min(self.stock.len()) {
+					for _ in 0..n_cards {
+						let card = self.stock.pop().unwrap();
+						self.waste.push(card);
+					}
+					return true
+				}
+			}
+			Move::RecycleWaste => {
+				if self.can_recycle() {
+					// turn the waste over, so that the bottom of the waste becomes the top of the stock
+					while let Some(card) = self.waste.pop() {
+						self.stock.push(card);
+					}
+					self.passes += 1;
+					return true
+				}
+			}
+		}
+		return false
+	}
+
+	pub fn exec_move_in_progress(&mut self, target:MouseTarget) {
+		if let Some(mip) = &self.move_in_progress {
+			for mv in mip.moves.clone() {
+				match mv {
+					Move::CardMove { dest, .. } => {
+						match dest {
+							MoveDest::ToPile(mip_pile_index) => {
+								match target {
+									MouseTarget::StockTop => {} // impossible
+									MouseTarget::StockDeck => {} // impossible
+									MouseTarget::Recycle => {} // impossible
+									MouseTarget::Foundation(_) => {} // not relevant for this move in progress
+									MouseTarget::EmptyPile(target_pile_index) => {
+										if mip_pile_index == target_pile_index {
+											self.exec_move(mv);
+											self.move_in_progress = None;
+											return
+										}
+									}
+									MouseTarget::PileCard{pile_index:target_pile_index, ..} => {
+										if mip_pile_index == target_pile_index {
+											self.exec_move(mv);
+											self.move_in_progress = None;
+											return
+										}
+									}
+								}
+							}
+							MoveDest::ToFoundation(mip_suit) => {
+								match target {
+									MouseTarget::StockTop => {} // impossible
+									MouseTarget::StockDeck => {} // impossible
+									MouseTarget::Recycle => {} // impossible
+									MouseTarget::Foundation(target_suit) => {
+										if mip_suit == target_suit {
+											self.exec_move(mv);
+											self.move_in_progress = None;
+											return
+										}
+									}
+									MouseTarget::EmptyPile(_) => {} // not relevant for this move in progress
+									MouseTarget::PileCard{..} => {} // not relevant for this move in progress
+								}
+							}
+						}
+					}
+					Move::DrawStock{..} | Move::RecycleWaste => {
+						self.exec_move(mv);
+					}
+				}
+			}
+		}
+		// if we got to here, it means the move failed. Clear it
+		self.move_in_progress = None;
+	}
+
+	// pops the top card off the given foundation.
+	// returns None if the foundation is empty.
+	pub fn pop_foundation(&mut self, suit:Suit) -> Option<Card> {
+		let top_card = self.foundation_top_card(suit)?;
+		if let Some(pred) = top_card.rank.pred() {
+			self.foundation_fill_levels.insert(suit, pred);
+		} else {
+			self.foundation_fill_levels.remove(&suit);
+		}
+		Some(top_card)
+	}
+
+	// if there are no moves to undo, does nothing
+	pub fn undo_move(&mut self) {
+		if let Some(entry) = self.history.pop() {
+			let mv = entry.mv;
+			self.unmove_cards(mv);
+			self.points = entry.points;
+			self.redo_stack.push(mv);
+			self.undos += 1;
+			self.debug_validate(mv);
+		}
+	}
+
+	// puts the cards back where they were before the move was made. The move has to be the last one
+	// that was made, or this may panic
+	pub(crate) fn unmove_cards(&mut self, mv:Move) {
+		match mv {
+			Move::CardMove{ card:_, src, dest } => {
+				match src {
+					MoveSrc::FromStock => {
+						match dest {
+							MoveDest::ToPile(dest_pile_index) => {
+								if let Some(card) = self.piles[dest_pile_index].visible.pop() {
+									self.waste.push(card);
+								}
+							}
+							MoveDest::ToFoundation(suit) => {
+								if let Some(card) = self.pop_foundation(suit) {
+									self.waste.push(card);
+								}
+							}
+						}
+					}
+					MoveSrc::FromFoundation(suit) => {
+						match dest {
+							MoveDest::ToPile(dest_pile_index) => {
+								if let Some(card) = self.piles[dest_pile_index].visible.pop() {
+									self.foundation_fill_levels.insert(suit, card.rank);
+								}
+							}
+							MoveDest::ToFoundation(_) => {} // impossible
+						}
+					}
+					MoveSrc::FromPile{ pile_index, n_cards, reveals_hidden_card, .. } => {
+						match dest {
+							MoveDest::ToPile(dest_pile_index) => {
+								let dest_pile = &mut self.piles[dest_pile_index];
+								let index = dest_pile.visible.len() - n_cards as usize;
+								let removed:Vec<Card> = dest_pile.visible.drain(index..).collect();
+
+								let src_pile = &mut self.piles[pile_index];
+
+								// check if need to re-hide the prev hidden card
+								if reveals_hidden_card {
+									if let Some(card_to_rehide) = src_pile.visible.pop() {
+										src_pile.hidden.push(card_to_rehide);
+									}
+								}
+
+								for card in removed {
+									src_pile.visible.push(card);
+								}
+							}
+							MoveDest::ToFoundation(suit) => {
+								if let Some(card) = self.pop_foundation(suit) {
+									let src_pile = &mut self.piles[pile_index];
+
+									// check if need to re-hide the prev hidden card
+									if reveals_hidden_card {
+										if let Some(card_to_rehide) = src_pile.visible.pop() {
+											src_pile.hidden.push(card_to_rehide);
+										}
+									}
+
+									src_pile.visible.push(card);
+								}
+							}
+						}
+					}
+				}
+			}
+			Move::DrawStock{ n_cards } => {
+				for _ in 0..n_cards {
+					if let Some(card) = self.waste.pop() {
+						self.stock.push(card);
+					}
+				}
+			}
+			Move::RecycleWaste => {
+				while let Some(card) = self.stock.pop() {
+					self.waste.push(card);
+				}
+				self.passes -= 1;
+			}
+		}
+	}
+
+	// re-applies the most recently undone move. Returns false if there's nothing to redo.
+	pub fn redo_move(&mut self) -> bool {
+		if let Some(mv) = self.redo_stack.pop() {
+			let applied = self.apply_move(mv);
+			if applied {
+				self.moves += 1;
+				self.debug_validate(mv);
+			}
+			return applied
+		}
+		return false
+	}
+
+	// returns true if every card is on the foundations
+	pub fn is_won(&self) -> bool {
+		return Suit::all().iter().all(|suit| self.foundation_fill_levels.get(suit) == Some(&Rank::King))
+	}
+
+	pub fn status(&self) -> GameStatus {
+		if self.is_won() {
+			return GameStatus::Won
+		}
+
+		for card in self.reachable_stock_cards() {
+			if self.can_place_card(card) {
+				return GameStatus::InProgress
+			}
+		}
+		// moving a whole pile into an empty one is always possible once it's been done once, but never helps
+		let card_can_move = self.legal_moves().iter()
+			.filter(|mv| !is_pointless(self, mv))
+			.any(|mv| matches!(mv, Move::CardMove{ src: MoveSrc::FromFoundation(_) | MoveSrc::FromPile{..}, .. }));
+		if card_can_move {
+			return GameStatus::InProgress
+		}
+		return GameStatus::NoMovesRemaining
+	}
+
+	// returns true if the stock is empty and the waste can be turned back over to start another pass
+	pub fn can_recycle(&self) -> bool {
+		return self.stock.is_empty() && !self.waste.is_empty() && self.rules.pass_limit.allows_another_pass(self.passes)
+	}
+
+	// returns every card that could become the top of the waste by drawing from (and recycling) the
+	// stock, assuming no other moves are made in between.
+	pub fn reachable_stock_cards(&self) -> Vec<Card> {
+		let n = self.rules.draw_count.n_cards();
+		let mut cards = Vec::new();
+		if let Some(top) = self.waste.last() {
+			cards.push(*top);
+		}
+
+		// the cards in the order they'd be drawn. After every draw, the last card drawn is on top
+		let draw_order: Vec<Card> = self.stock.iter().rev().copied().collect();
+		cards.extend(every_nth_and_last(&draw_order, n));
+
+		// after a recycle, the whole waste then the rest of the stock gets drawn again from the start
+		if self.rules.pass_limit.allows_another_pass(self.passes) {
+			let all_cards: Vec<Card> = self.waste.iter().chain(draw_order.iter()).copied().collect();
+			cards.extend(every_nth_and_last(&all_cards, n));
+		}
+
+		return cards
+	}
+
+	// returns true if the given (single) card could be moved onto a foundation or a pile.
+	fn can_place_card(&self, card:Card) -> bool {
+		return self.fits_on_foundation(card) || (0..self.piles.len()).any(|pile_index| self.fits_on_pile(card, pile_index))
+	}
+
+	// returns true once the clock has started, ie. once the first move has been made
+	pub fn has_started(&self) -> bool {
+		return self.moves > 0 || self.elapsed > 0.
+	}
+
+	// advances the game clock. The clock starts with the first move, and stops once the game is over.
+	pub fn tick(&mut self, dt:f64) {
+		if self.has_started() && self.status() == GameStatus::InProgress {
+			self.elapsed += dt;
+		}
+	}
+
+	// makes the best looking move. Returns false if the game's over, or if the game is going round in
+	// circles, as pressing A again would only carry on round them.
+	pub fn auto_move(&mut self) -> bool {
+		if self.status() != GameStatus::InProgress || self.is_looping() {
+			return false
+		}
+		return self.play_strategy(&mut Greedy)
+	}
+}
+
+// a move in the history, and what's remembered about the position it was made in
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+	pub mv: Move,
+	pub points: i32, // the points from before the move, so undo can restore them
+	pub time: f64, // the value of `elapsed` when the move was made, for replays
+	pub position_hash: u64, // the position before the move, for spotting repeated positions
+}
+
+// where the cards for a move come from, for generating the moves from just one place
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Source {
+	Stock, // drawing from the stock, or recycling the waste
+	Waste,
+	Foundation(Suit),
+	Pile{ pile_index:usize, target_card_index:usize }, // the visible cards from target_card_index up
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameStatus {
+	InProgress,
+	Won, // every card is on the foundations
+	NoMovesRemaining, // no card can be moved anywhere, even after cycling through the stock
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MouseTarget {
+	StockTop, // the top card of the waste
+	StockDeck, // the face-down stock
+	Recycle, // the empty space where the stock was, once it's been drawn through
+	Foundation(Suit), // one of the foundation piles
+	EmptyPile(usize), // an empty pile (valid target if moving a King to an empty space)
+	PileCard{ // a particular card in a pile
+		pile_index:usize, // 0 is the leftmost pile
+		n_cards:u8, // 1 = only the top card, 2 = two top cards, etc
+		target_card:Card, // the card that was targeted
+		target_card_index:usize, // the index into visible of the targeted card
+		top:f32, // the y-coord of the top of the targeted card
+	},
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveInProgress {
+	pub target: MouseTarget,
+	pub moves: Vec<Move>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Move {
+	CardMove{
+		card: Card,
+		src: MoveSrc,
+		dest: MoveDest,
+	},
+	DrawStock{ // turns cards from the stock onto the waste
+		n_cards:u8, // normally the rule's draw count, but fewer if the stock is running out
+	},
+	RecycleWaste, // turns the waste back over to become the stock, once the stock is empty
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)]
+pub enum MoveSrc {
+	FromStock, // the top card of the waste
+	FromFoundation(Suit),
+	FromPile{
+		pile_index:usize, // 0 is the leftmost pile
+		n_cards:u8, // 1 = only the top card, 2 = two top cards, etc
+		progress_value:usize, // a heuristic of how much progress this move represents
+		reveals_hidden_card:bool,
+		target_card_index:usize, // the index into visible of the targeted card. Note: if this is
+								 // 0 and the hidden size > 0, that means the move uncovers a
+								 // hidden card
+	},
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MoveDest {
+	ToPile(usize),
+	ToFoundation(Suit),
+}
+
+// returns every nth item (ie. the items at n-1, 2n-1, ...) plus the last item
+fn every_nth_and_last(items: &[Card], n: usize) -> Vec<Card> {
+	let mut result: Vec<Card> = items.iter().skip(n - 1).step_by(n).copied().collect();
+	if items.len() % n != 0 {
+		if let Some(last) = items.last() {
+			result.push(*last);
+		}
+	}
+	return result
+}
@@ -0,0 +1,63 @@
+//! Suggests moves to the player.
+//!
+//! If the solver can find a win within a small budget then its first move is the best hint. The
+//! rest of the legal moves are ranked by looking two moves ahead and scoring the resulting positions,
+//! so that there are sensible alternatives to cycle through (and a sensible suggestion even when the
+//! solver gives up).
+
+use crate::game::{Game, Move};
+use crate::solver::{is_pointless, solve, SolveResult, SolverConfig};
+use std::time::Duration;
+
+// the solver has to answer quickly enough not to stall the frontend
+const HINT_SOLVER_CONFIG: SolverConfig = SolverConfig {
+	max_nodes: 50_000,
+	time_limit: Some(Duration::from_millis(250)),
+};
+
+// returns every sensible move from the current position, best first.
+pub fn hints(game: &Game) -> Vec<Move> {
+	let mut game = game.clone();
+	game.move_in_progress = None;
+
+	let candidates: Vec<Move> = game.legal_moves().into_iter().filter(|mv| !is_pointless(&game, mv)).collect();
+	let mut scored: Vec<(i32, Move)> = candidates.into_iter()
+		.filter_map(|mv| Some((lookahead_score(&mut game, mv)?, mv)))
+		.collect();
+	scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+	let mut moves: Vec<Move> = scored.into_iter().map(|(_, mv)| mv).collect();
+
+	if let SolveResult::Solved(solution) = solve(&game, HINT_SOLVER_CONFIG).result {
+		if let Some(first) = solution.first() {
+			moves.retain(|mv| mv != first);
+			moves.insert(0, *first);
+		}
+	}
+	return moves
+}
+
+// the score of the best position reachable by making the given move and then (optionally) one more.
+// returns None if the move isn't legal.
+fn lookahead_score(game: &mut Game, mv: Move) -> Option<i32> {
+	if !game.exec_move(mv) {
+		return None
+	}
+	let mut best = evaluate(game);
+	for next in game.legal_moves() {
+		if game.exec_move(next) {
+			best = best.max(evaluate(game));
+			game.undo_move();
+		}
+	}
+	game.undo_move();
+	return Some(best)
+}
+
+// a rough measure of how close the position is to being won. Higher is better.
+fn evaluate(game: &Game) -> i32 {
+	let foundation_cards: i32 = game.foundation_fill_levels.values().map(|rank| rank.index() as i32 + 1).sum();
+	let hidden_cards: i32 = game.piles.iter().map(|pile| pile.hidden.len() as i32).sum();
+	let empty_piles = game.piles.iter().filter(|pile| pile.is_empty()).count() as i32;
+	let stock_cards = (game.stock.len() + game.waste.len()) as i32;
+	return foundation_cards*10 - hidden_cards*5 + empty_piles*3 - stock_cards
+}
@@ -0,0 +1,42 @@
+//! The rules engine for the solitaire/klondike clone.
+//!
+//! Everything in here is headless: the game state, move generation and undo all live in this crate
+//! with no dependency on a graphics library, so it can be tested, simulated or driven by any
+//! frontend.
+
+#![allow(clippy::needless_return)]
+
+mod card;
+mod cascade;
+mod game;
+mod hint;
+mod notation;
+mod pile;
+mod position;
+mod repetition;
+mod replay;
+mod rng;
+mod save;
+mod score;
+mod solver;
+mod stats;
+mod strategy;
+mod validate;
+mod versioned;
+
+pub use card::{Card, Colour, Rank, Suit};
+pub use cascade::{Cascade, CascadeCard, CascadeLayout, CASCADE_STEPS_PER_SEC};
+pub use game::{DrawCount, Game, GameStatus, HistoryEntry, MouseTarget, Move, MoveDest, MoveInProgress, MoveSrc, PassLimit, Rules, N_PILES};
+pub use hint::hints;
+pub use notation::{MoveText, MoveTextSrc, NotationError};
+pub use pile::Pile;
+pub use position::PositionError;
+pub use repetition::REPEAT_LIMIT;
+pub use replay::{Replay, Replayer, TimedMove, REPLAY_VERSION};
+pub use rng::Rng;
+pub use save::{SaveError, SAVE_VERSION};
+pub use score::{time_text, Scoring};
+pub use solver::{solve, Solution, SolveResult, SolverConfig};
+pub use stats::{Stats, VariantStats, STATS_VERSION};
+pub use strategy::{Greedy, RandomMoves, Strategy};
+pub use validate::ValidationError;
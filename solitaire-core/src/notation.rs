@@ -0,0 +1,394 @@
+//! A compact text notation for cards, moves and whole boards, for pasting into bug reports and tests.
+//!
+//! Cards are two characters, rank then suit: `AS`, `TD`, `9H`.
+//!
+//! Moves are written as `source>destination`, with piles numbered from 1 to 7:
+//! - `W>5`: the top card of the waste onto pile 5
+//! - `3>F`: the top card of pile 3 onto its foundation
+//! - `6:2>1`: the top 2 cards of pile 6 onto pile 1
+//! - `FH>2`: the top card of the hearts foundation onto pile 2
+//! - `D`: draw from the stock, `R`: turn the waste back over into the stock
+//!
+//! A move only says which cards go where, so the text parses into a `MoveText`, and turning that
+//! into a `Move` needs the position it's made in (see `Game::resolve_move` and `Game::parse_move`).
+//!
+//! A board is written one part per line, with every list of cards running from bottom to top and the
+//! hidden cards of each pile before a `|`:
+//!
+//! ```text
+//! draw: 1
+//! pass limit: unlimited
+//! scoring: standard
+//! pass: 1
+//! stock: 2C 9D QS
+//! waste: 4H
+//! foundations: AD - 3H -
+//! 1: KC QD
+//! 2: 7S | 8H
+//! 3:
+//! ...
+//! ```
+//!
+//! The foundations are listed in the order they're shown on screen, by their top card (`-` if empty).
+//! Blank lines and lines starting with `#` are ignored.
+
+use crate::card::{Card, Rank, Suit};
+use crate::game::{DrawCount, Game, Move, MoveDest, MoveSrc, PassLimit, N_PILES};
+use crate::pile::Pile;
+use crate::score::Scoring;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotationError(pub String);
+
+impl fmt::Display for NotationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return write!(f, "{}", self.0)
+	}
+}
+
+impl std::error::Error for NotationError {}
+
+fn error<T>(msg: String) -> Result<T, NotationError> {
+	return Err(NotationError(msg))
+}
+
+impl Rank {
+	pub fn to_char(&self) -> char {
+		return match self {
+			Rank::Ten => 'T',
+			_ => self.letter().chars().next().unwrap(),
+		}
+	}
+
+	pub fn from_char(c: char) -> Option<Rank> {
+		return match c.to_ascii_uppercase() {
+			'A' => Some(Rank::Ace),
+			'T' => Some(Rank::Ten),
+			'J' => Some(Rank::Jack),
+			'Q' => Some(Rank::Queen),
+			'K' => Some(Rank::King),
+			'2'..='9' => Rank::from_index(c as i8 - '1' as i8),
+			_ => None,
+		}
+	}
+}
+
+impl Suit {
+	pub fn to_char(&self) -> char {
+		return match self {
+			Suit::Diamonds => 'D',
+			Suit::Clubs => 'C',
+			Suit::Hearts => 'H',
+			Suit::Spades => 'S',
+		}
+	}
+
+	pub fn from_char(c: char) -> Option<Suit> {
+		return match c.to_ascii_uppercase() {
+			'D' => Some(Suit::Diamonds),
+			'C' => Some(Suit::Clubs),
+			'H' => Some(Suit::Hearts),
+			'S' => Some(Suit::Spades),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Card {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return write!(f, "{}{}", self.rank.to_char(), self.suit.to_char())
+	}
+}
+
+impl FromStr for Card {
+	type Err = NotationError;
+
+	// also accepts lower case, and "10" for tens
+	fn from_str(s: &str) -> Result<Card, NotationError> {
+		let s = s.trim();
+		let (rank, suit) = match s.len() {
+			2 => (Rank::from_char(s.chars().next().unwrap()), s.chars().nth(1).and_then(Suit::from_char)),
+			3 if s.starts_with("10") => (Some(Rank::Ten), s.chars().nth(2).and_then(Suit::from_char)),
+			_ => (None, None),
+		};
+		return match (rank, suit) {
+			(Some(rank), Some(suit)) => Ok(Card::new(suit, rank)),
+			_ => error(format!("\"{}\" isn't a card", s)),
+		}
+	}
+}
+
+// a move as it's written, before it's been matched up with a position. It only says which cards
+// go where, so it can be parsed without knowing the position (see `Game::resolve_move`).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MoveText {
+	DrawStock, // D
+	RecycleWaste, // R
+	CardMove{ src: MoveTextSrc, to_pile: Option<usize> }, // to_pile is None for moves to the foundation
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MoveTextSrc {
+	Waste, // W
+	Foundation(Suit), // FH
+	Pile{ pile_index: usize, n_cards: usize }, // 3, or 3:2 for more than one card
+}
+
+impl From<Move> for MoveText {
+	fn from(mv: Move) -> MoveText {
+		return match mv {
+			Move::DrawStock{..} => MoveText::DrawStock,
+			Move::RecycleWaste => MoveText::RecycleWaste,
+			Move::CardMove{ src, dest, .. } => {
+				let src = match src {
+					MoveSrc::FromStock => MoveTextSrc::Waste,
+					MoveSrc::FromFoundation(suit) => MoveTextSrc::Foundation(suit),
+					MoveSrc::FromPile{ pile_index, n_cards, .. } => MoveTextSrc::Pile{ pile_index, n_cards: n_cards as usize },
+				};
+				let to_pile = match dest {
+					MoveDest::ToPile(pile_index) => Some(pile_index),
+					MoveDest::ToFoundation(_) => None,
+				};
+				MoveText::CardMove{ src, to_pile }
+			}
+		}
+	}
+}
+
+impl fmt::Display for MoveText {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return match self {
+			MoveText::DrawStock => write!(f, "D"),
+			MoveText::RecycleWaste => write!(f, "R"),
+			MoveText::CardMove{ src, to_pile } => {
+				match src {
+					MoveTextSrc::Waste => write!(f, "W")?,
+					MoveTextSrc::Foundation(suit) => write!(f, "F{}", suit.to_char())?,
+					MoveTextSrc::Pile{ pile_index, n_cards: 1 } => write!(f, "{}", pile_index + 1)?,
+					MoveTextSrc::Pile{ pile_index, n_cards } => write!(f, "{}:{}", pile_index + 1, n_cards)?,
+				}
+				match to_pile {
+					Some(pile_index) => write!(f, ">{}", pile_index + 1),
+					None => write!(f, ">F"),
+				}
+			}
+		}
+	}
+}
+
+impl FromStr for MoveText {
+	type Err = NotationError;
+
+	// also accepts lower case
+	fn from_str(s: &str) -> Result<MoveText, NotationError> {
+		let s = s.trim();
+		let upper = s.to_ascii_uppercase();
+		match upper.as_str() {
+			"D" => return Ok(MoveText::DrawStock),
+			"R" => return Ok(MoveText::RecycleWaste),
+			_ => {}
+		}
+
+		let (src, dest) = match upper.split_once('>') {
+			Some(parts) => parts,
+			None => return error(format!("\"{}\" isn't a move", s)),
+		};
+		let to_pile = match dest.trim() {
+			"F" => None,
+			pile => Some(parse_pile_number(pile)?),
+		};
+		let src = src.trim();
+		let src = if src == "W" {
+			MoveTextSrc::Waste
+		} else if let Some(suit) = src.strip_prefix('F') {
+			let mut chars = suit.chars();
+			match (chars.next().and_then(Suit::from_char), chars.next()) {
+				(Some(suit), None) => MoveTextSrc::Foundation(suit),
+				_ => return error(format!("\"{}\" isn't a foundation", src)),
+			}
+		} else {
+			let (pile, n_cards) = match src.split_once(':') {
+				Some((pile, n_cards)) => match n_cards.trim().parse::<usize>() {
+					Ok(n_cards) if n_cards > 0 => (pile, n_cards),
+					_ => return error(format!("\"{}\" isn't a number of cards", n_cards)),
+				},
+				None => (src, 1),
+			};
+			MoveTextSrc::Pile{ pile_index: parse_pile_number(pile)?, n_cards }
+		};
+		return Ok(MoveText::CardMove{ src, to_pile })
+	}
+}
+
+impl fmt::Display for Move {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return MoveText::from(*self).fmt(f)
+	}
+}
+
+// parses a pile number (counting from 1) into a pile index
+fn parse_pile_number(s: &str) -> Result<usize, NotationError> {
+	return match s.trim().parse::<usize>() {
+		Ok(n) if n >= 1 && n <= N_PILES as usize => Ok(n - 1),
+		_ => error(format!("\"{}\" isn't a pile number (1 to {})", s, N_PILES)),
+	}
+}
+
+impl Game {
+	// finds the legal move in this position that matches the text
+	pub fn resolve_move(&self, text: MoveText) -> Result<Move, NotationError> {
+		return match self.legal_moves().into_iter().find(|mv| MoveText::from(*mv) == text) {
+			Some(mv) => Ok(mv),
+			None => error(format!("\"{}\" isn't a legal move in this position", text)),
+		}
+	}
+
+	// turns a move written in the notation above into the matching legal move in this position
+	pub fn parse_move(&self, s: &str) -> Result<Move, NotationError> {
+		return self.resolve_move(s.parse()?)
+	}
+}
+
+fn write_cards(f: &mut fmt::Formatter, cards: &[Card]) -> fmt::Result {
+	for card in cards {
+		write!(f, " {}", card)?;
+	}
+	return Ok(())
+}
+
+fn parse_cards(s: &str) -> Result<Vec<Card>, NotationError> {
+	return s.split_whitespace().map(Card::from_str).collect()
+}
+
+impl fmt::Display for Game {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "draw: {}", self.rules.draw_count.n_cards())?;
+		match self.rules.pass_limit {
+			PassLimit::Unlimited => writeln!(f, "pass limit: unlimited")?,
+			PassLimit::Passes(limit) => writeln!(f, "pass limit: {}", limit)?,
+		}
+		match self.rules.scoring {
+			Scoring::Standard => writeln!(f, "scoring: standard")?,
+			Scoring::Vegas => writeln!(f, "scoring: vegas")?,
+		}
+		writeln!(f, "pass: {}", self.passes)?;
+
+		write!(f, "stock:")?;
+		write_cards(f, &self.stock)?;
+		write!(f, "\nwaste:")?;
+		write_cards(f, &self.waste)?;
+
+		write!(f, "\nfoundations:")?;
+		for suit in foundation_order() {
+			match self.foundation_top_card(suit) {
+				Some(card) => write!(f, " {}", card)?,
+				None => write!(f, " -")?,
+			}
+		}
+		writeln!(f)?;
+
+		for (i, pile) in self.piles.iter().enumerate() {
+			write!(f, "{}:", i + 1)?;
+			if !pile.hidden.is_empty() {
+				write_cards(f, &pile.hidden)?;
+				write!(f, " |")?;
+			}
+			write_cards(f, &pile.visible)?;
+			writeln!(f)?;
+		}
+		return Ok(())
+	}
+}
+
+// the suits in the order their foundations are shown on screen
+fn foundation_order() -> Vec<Suit> {
+	let mut suits = Suit::all().to_vec();
+	suits.sort_by_key(|suit| suit.foundation_index());
+	return suits
+}
+
+impl FromStr for Game {
+	type Err = NotationError;
+
+	// reads a board written by Display. Only the layout is checked here, not whether the cards make
+	// up a proper deck.
+	fn from_str(s: &str) -> Result<Game, NotationError> {
+		let mut game = Game::empty(Default::default());
+		game.piles = vec![Pile::new(); N_PILES as usize];
+		let mut seen_piles = vec![false; N_PILES as usize];
+
+		for (line_index, line) in s.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue
+			}
+			let at_line = |msg: String| NotationError(format!("line {}: {}", line_index + 1, msg));
+			let (key, value) = line.split_once(':').ok_or_else(|| at_line(format!("expected \"name: value\", found \"{}\"", line)))?;
+			let value = value.trim();
+			match key.trim().to_ascii_lowercase().as_str() {
+				"draw" => {
+					game.rules.draw_count = match value {
+						"1" => DrawCount::One,
+						"3" => DrawCount::Three,
+						_ => return Err(at_line(format!("the draw must be 1 or 3, not \"{}\"", value))),
+					}
+				}
+				"pass limit" => {
+					game.rules.pass_limit = match value {
+						"unlimited" => PassLimit::Unlimited,
+						_ => match value.parse::<u32>() {
+							Ok(limit) if limit > 0 => PassLimit::Passes(limit),
+							_ => return Err(at_line(format!("the pass limit must be \"unlimited\" or a number, not \"{}\"", value))),
+						},
+					}
+				}
+				"scoring" => {
+					game.rules.scoring = match value {
+						"standard" => Scoring::Standard,
+						"vegas" => Scoring::Vegas,
+						_ => return Err(at_line(format!("the scoring must be \"standard\" or \"vegas\", not \"{}\"", value))),
+					};
+					game.points = game.rules.scoring.initial_points();
+				}
+				"pass" => {
+					game.passes = match value.parse::<u32>() {
+						Ok(passes) if passes > 0 => passes,
+						_ => return Err(at_line(format!("\"{}\" isn't a pass number", value))),
+					}
+				}
+				"stock" => game.stock = parse_cards(value).map_err(|err| at_line(err.0))?,
+				"waste" => game.waste = parse_cards(value).map_err(|err| at_line(err.0))?,
+				"foundations" => {
+					let tops: Vec<&str> = value.split_whitespace().collect();
+					if tops.len() != 4 {
+						return Err(at_line(format!("expected 4 foundations, found {}", tops.len())))
+					}
+					for (suit, top) in foundation_order().into_iter().zip(tops) {
+						if top == "-" {
+							continue
+						}
+						let card = Card::from_str(top).map_err(|err| at_line(err.0))?;
+						if card.suit != suit {
+							return Err(at_line(format!("{} can't be on the {:?} foundation", card, suit)))
+						}
+						game.foundation_fill_levels.insert(suit, card.rank);
+					}
+				}
+				pile => {
+					let pile_index = parse_pile_number(pile).map_err(|_| at_line(format!("unknown line \"{}\"", key)))?;
+					let (hidden, visible) = value.split_once('|').unwrap_or(("", value));
+					game.piles[pile_index].hidden = parse_cards(hidden).map_err(|err| at_line(err.0))?;
+					game.piles[pile_index].visible = parse_cards(visible).map_err(|err| at_line(err.0))?;
+					seen_piles[pile_index] = true;
+				}
+			}
+		}
+
+		if let Some(missing) = seen_piles.iter().position(|seen| !seen) {
+			return error(format!("pile {} is missing", missing + 1))
+		}
+		return Ok(game)
+	}
+}
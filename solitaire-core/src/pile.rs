@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use crate::card::Card;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Pile {
+	pub hidden: Vec<Card>,
+	pub visible: Vec<Card>,
+}
+
+impl Pile {
+	pub fn new() -> Pile {
+		return Pile {
+			hidden: Vec::new(),
+			visible: Vec::new(),
+		};
+	}
+
+	pub fn len(&self) -> usize {
+		self.hidden.len() + self.visible.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.hidden.is_empty() && self.visible.is_empty()
+	}
+
+	pub fn top_card(&self) -> Option<Card> {
+		self.visible.as_slice().last().copied()
+	}
+}
+
+impl Default for Pile {
+	fn default() -> Pile {
+		Pile::new()
+	}
+}
@@ -0,0 +1,64 @@
+//! Games set up from a hand-written position rather than dealt from a shuffled deck, for reproducing
+//! bugs and building puzzles.
+//!
+//! A position is written in the board notation (see the `notation` module), and is validated to make
+//! sure it could actually come up in a game: every card in the deck appears exactly once, the face up
+//! cards in each pile form a proper run, and so on.
+
+use crate::game::Game;
+use crate::notation::NotationError;
+use crate::validate::ValidationError;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum PositionError {
+	Io(std::io::Error),
+	Syntax(NotationError),
+	Invalid(ValidationError), // the position is written correctly, but couldn't come up in a game
+}
+
+impl fmt::Display for PositionError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return match self {
+			PositionError::Io(err) => write!(f, "couldn't read the file: {}", err),
+			PositionError::Syntax(err) => write!(f, "{}", err),
+			PositionError::Invalid(err) => write!(f, "{}", err),
+		}
+	}
+}
+
+impl std::error::Error for PositionError {}
+
+impl From<std::io::Error> for PositionError {
+	fn from(err: std::io::Error) -> PositionError {
+		PositionError::Io(err)
+	}
+}
+
+impl From<NotationError> for PositionError {
+	fn from(err: NotationError) -> PositionError {
+		PositionError::Syntax(err)
+	}
+}
+
+impl From<ValidationError> for PositionError {
+	fn from(err: ValidationError) -> PositionError {
+		PositionError::Invalid(err)
+	}
+}
+
+impl Game {
+	// sets up a game from a position written in the board notation
+	pub fn from_position(s: &str) -> Result<Game, PositionError> {
+		let game: Game = s.parse()?;
+		game.validate()?;
+		return Ok(game)
+	}
+
+	pub fn load_position(path: &Path) -> Result<Game, PositionError> {
+		let s = fs::read_to_string(path)?;
+		return Game::from_position(&s)
+	}
+}
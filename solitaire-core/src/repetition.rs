@@ -0,0 +1,96 @@
+//! Spotting when a game is going round in circles.
+//!
+//! Each position is identified by a Zobrist hash: every card has a random key for each place it
+//! could be (each position in the stock, the waste or a pile, or on top of a foundation), and the
+//! hash of a position is the XOR of the keys for where all its cards are. The keys come from
+//! SplitMix64 rather than a stored table, so the same position always hashes the same, even across
+//! runs.
+//!
+//! The game keeps the hash of the position before each move, so it can tell when the current
+//! position has come up before, eg. when auto-play cycles through the stock pass after pass without
+//! doing anything, or shuffles a king back and forth between empty piles.
+
+use crate::card::{Card, Suit};
+use crate::game::{Game, Move, PassLimit};
+use crate::rng::Rng;
+
+// a game is going round in circles once the same position has come back this many times
+pub const REPEAT_LIMIT: usize = 3;
+
+// mixed into the keys, so that hashes don't come out the same as the first numbers of a deal
+const KEY_SEED: u64 = 0x5EED_2B0B_215F_0000;
+const PASSES_SEED: u64 = 0x9A55_E5C0_17E7_0000;
+
+// the places a card can be, for choosing its key. Each pile has room for its hidden and visible
+// cards one after the other.
+const STOCK_PLACE: u64 = 0;
+const WASTE_PLACE: u64 = 64;
+const FOUNDATION_PLACE: u64 = 128;
+const PILES_PLACE: u64 = 256;
+const PILE_PLACES: u64 = 64;
+const VISIBLE_PLACE: u64 = 32;
+
+fn card_key(card: Card, place: u64) -> u64 {
+	let card_index = card.suit.foundation_index() as u64 * 13 + card.rank.index() as u64;
+	return Rng::new(KEY_SEED ^ (card_index << 12) ^ place).next_u64()
+}
+
+impl Game {
+	// identifies the position of the cards. The number of passes through the stock is only included
+	// when it's limited, so that cycling through the stock without changing anything counts as a
+	// repeat.
+	pub fn position_hash(&self) -> u64 {
+		let mut hash = 0;
+		for (i, card) in self.stock.iter().enumerate() {
+			hash ^= card_key(*card, STOCK_PLACE + i as u64);
+		}
+		for (i, card) in self.waste.iter().enumerate() {
+			hash ^= card_key(*card, WASTE_PLACE + i as u64);
+		}
+		// the top card of a foundation says which cards are underneath it
+		for suit in Suit::all() {
+			if let Some(top) = self.foundation_top_card(*suit) {
+				hash ^= card_key(top, FOUNDATION_PLACE);
+			}
+		}
+		for (pile_index, pile) in self.piles.iter().enumerate() {
+			let pile_place = PILES_PLACE + pile_index as u64 * PILE_PLACES;
+			for (i, card) in pile.hidden.iter().enumerate() {
+				hash ^= card_key(*card, pile_place + i as u64);
+			}
+			for (i, card) in pile.visible.iter().enumerate() {
+				hash ^= card_key(*card, pile_place + VISIBLE_PLACE + i as u64);
+			}
+		}
+		if let PassLimit::Passes(_) = self.rules.pass_limit {
+			hash ^= Rng::new(PASSES_SEED ^ self.passes as u64).next_u64();
+		}
+		return hash
+	}
+
+	// the number of times the current position has come up before in this game. Drawing from the
+	// stock passes through the same positions on every pass, so positions reached by a draw aren't
+	// counted: cycling through the stock without doing anything is only a repeat once a whole pass
+	// has gone by, when the waste has been turned back over.
+	pub fn times_seen(&self) -> usize {
+		if self.reached_by_draw(self.history.len()) {
+			return 0
+		}
+		let hash = self.position_hash();
+		return self.history.iter().enumerate()
+			.filter(|(i, entry)| entry.position_hash == hash && !self.reached_by_draw(*i))
+			.count()
+	}
+
+	// returns true if the position before the given move in the history (or the current position,
+	// for the length of the history) was reached by drawing from the stock
+	fn reached_by_draw(&self, history_index: usize) -> bool {
+		return history_index > 0 && matches!(self.history[history_index - 1].mv, Move::DrawStock{..})
+	}
+
+	// returns true once the current position has come back REPEAT_LIMIT times, ie. nothing the
+	// player (or auto-play) has been doing is getting anywhere
+	pub fn is_looping(&self) -> bool {
+		return self.times_seen() >= REPEAT_LIMIT
+	}
+}
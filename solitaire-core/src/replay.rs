@@ -0,0 +1,148 @@
+//! Replays: a record of a whole game that can be played back move by move.
+//!
+//! A replay only holds the deal number, the rules and the moves that were made (with the time each
+//! one was made), so the game is dealt again from the deal number and the moves are made again on
+//! top of it. Undone moves aren't part of a replay, just the moves that led to the final position.
+
+use crate::game::{Game, GameStatus, Move, Rules};
+use crate::save::SaveError;
+use crate::versioned::{self, Versioned};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+	pub seed: u64,
+	pub rules: Rules,
+	pub moves: Vec<TimedMove>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimedMove {
+	pub mv: Move,
+	pub time: f64, // seconds since the start of the game
+}
+
+impl Versioned for Replay {
+	const KEY: &'static str = "replay";
+	const VERSION: u32 = REPLAY_VERSION;
+}
+
+impl Replay {
+	// returns None for games that weren't dealt from a deal number, as they can't be dealt again
+	pub fn from_game(game: &Game) -> Option<Replay> {
+		let seed = game.seed?;
+		let moves = game.history.iter().map(|entry| TimedMove { mv: entry.mv, time: entry.time }).collect();
+		return Some(Replay { seed, rules: game.rules, moves })
+	}
+
+	// the replay to write once a game has been won, or abandoned for a new deal, which is then marked
+	// as saved so that it's only written once. Returns None if it's already been saved, or hadn't been
+	// started. A game with no moves remaining isn't over yet, as the player can still undo.
+	pub fn to_save(game: &mut Game, abandoned: bool) -> Option<Replay> {
+		if game.replay_saved || !game.has_started() || !(abandoned || game.status() == GameStatus::Won) {
+			return None
+		}
+		game.replay_saved = true;
+		return Replay::from_game(game)
+	}
+
+	pub fn to_json(&self) -> String {
+		return versioned::to_json(self)
+	}
+
+	pub fn from_json(s: &str) -> Result<Replay, SaveError> {
+		return versioned::from_json(s)
+	}
+
+	// writes the replay to the given path, creating its parent directory if needed
+	pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+		return versioned::save(self, path)
+	}
+
+	pub fn load(path: &Path) -> Result<Replay, SaveError> {
+		return versioned::load(path)
+	}
+}
+
+// steps backwards and forwards through a replay
+pub struct Replayer {
+	replay: Replay,
+	game: Game,
+	position: usize, // the number of moves that have been made
+}
+
+impl Replayer {
+	// checks that every move in the replay can actually be made, so that stepping can't fail later
+	pub fn new(replay: Replay) -> Result<Replayer, SaveError> {
+		let mut game = Game::from_seed(replay.seed, replay.rules);
+		for (i, timed) in replay.moves.iter().enumerate() {
+			if !game.exec_move(timed.mv) {
+				return Err(SaveError::Corrupt(format!("move {} ({:?}) can't be made", i + 1, timed.mv)))
+			}
+		}
+		return Ok(Replayer { game: Game::from_seed(replay.seed, replay.rules), replay, position: 0 })
+	}
+
+	pub fn game(&self) -> &Game {
+		return &self.game
+	}
+
+	pub fn replay(&self) -> &Replay {
+		return &self.replay
+	}
+
+	pub fn position(&self) -> usize {
+		return self.position
+	}
+
+	pub fn is_at_end(&self) -> bool {
+		return self.position == self.replay.moves.len()
+	}
+
+	// makes the next move. Returns false if there are no more moves.
+	pub fn step_forward(&mut self) -> bool {
+		let timed = match self.replay.moves.get(self.position) {
+			Some(timed) => *timed,
+			None => return false,
+		};
+		self.game.elapsed = timed.time;
+		self.game.exec_move(timed.mv);
+		self.position += 1;
+		return true
+	}
+
+	// takes back the last move. Returns false if already at the start.
+	pub fn step_back(&mut self) -> bool {
+		if self.position == 0 {
+			return false
+		}
+		self.game.undo_move();
+		self.position -= 1;
+		self.game.elapsed = self.game.history.last().map_or(0., |entry| entry.time);
+		return true
+	}
+
+	// steps backwards or forwards to the given position (clamped to the length of the replay)
+	pub fn seek(&mut self, position: usize) {
+		let position = position.min(self.replay.moves.len());
+		while self.position < position {
+			self.step_forward();
+		}
+		while self.position > position {
+			self.step_back();
+		}
+	}
+
+	// seconds between the last move made and the next one, as they were originally played
+	pub fn time_to_next_move(&self) -> Option<f64> {
+		let next = self.replay.moves.get(self.position)?;
+		let prev_time = match self.position {
+			0 => 0.,
+			_ => self.replay.moves[self.position - 1].time,
+		};
+		return Some((next.time - prev_time).max(0.))
+	}
+}
@@ -0,0 +1,49 @@
+//! A small, self-contained pseudo-random number generator used for dealing.
+//!
+//! Deals have to be reproducible from their deal number forever, so rather than depending on an
+//! external crate whose algorithm might change between versions, this is a straight implementation
+//! of SplitMix64 (Steele, Lea & Flood, "Fast Splittable Pseudorandom Number Generators", 2014).
+//! It only uses wrapping `u64` arithmetic, so the output is identical on every platform.
+//!
+//! **Do not change the algorithm, the constants or the way [`Rng::shuffle`] consumes numbers**:
+//! doing so would silently change every deal number that players have shared.
+
+pub struct Rng {
+	state: u64,
+}
+
+impl Rng {
+	pub fn new(seed: u64) -> Rng {
+		return Rng { state: seed }
+	}
+
+	// returns the next 64 random bits
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		return z ^ (z >> 31)
+	}
+
+	// returns a uniformly distributed number in 0..n. n must be non-zero.
+	// uses rejection sampling so that there's no modulo bias.
+	pub fn gen_below(&mut self, n: u64) -> u64 {
+		assert!(n > 0, "gen_below called with n = 0");
+		let zone = u64::MAX - (u64::MAX % n);
+		loop {
+			let x = self.next_u64();
+			if x < zone {
+				return x % n
+			}
+		}
+	}
+
+	// Fisher-Yates shuffle, walking from the back of the slice to the front.
+	pub fn shuffle<T>(&mut self, items: &mut [T]) {
+		for i in (1..items.len()).rev() {
+			let j = self.gen_below(i as u64 + 1) as usize;
+			items.swap(i, j);
+		}
+	}
+}
@@ -0,0 +1,75 @@
+//! Saving a game in progress to disk and loading it back.
+//!
+//! Save files are JSON with a version number alongside the game (see `versioned`). Whenever the layout of `Game` (or
+//! anything inside it) changes in a way that old files can't be read, bump `SAVE_VERSION` so that
+//! old saves are rejected cleanly instead of being misread.
+
+use crate::game::Game;
+use crate::versioned::{self, Versioned};
+use std::fmt;
+use std::path::Path;
+
+pub const SAVE_VERSION: u32 = 1;
+
+// an error reading or writing a saved game (or any of the other files kept on disk)
+#[derive(Debug)]
+pub enum SaveError {
+	Io(std::io::Error),
+	Corrupt(String), // the file couldn't be parsed, or it doesn't describe a valid game
+	IncompatibleVersion{ found: u32, expected: u32 },
+}
+
+impl fmt::Display for SaveError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return match self {
+			SaveError::Io(err) => write!(f, "couldn't access the file: {}", err),
+			SaveError::Corrupt(reason) => write!(f, "the file is corrupt: {}", reason),
+			SaveError::IncompatibleVersion{ found, expected } => {
+				write!(f, "the file is from an incompatible version (found version {}, expected {})", found, expected)
+			}
+		}
+	}
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+	fn from(err: std::io::Error) -> SaveError {
+		SaveError::Io(err)
+	}
+}
+
+impl Versioned for Game {
+	const KEY: &'static str = "game";
+	const VERSION: u32 = SAVE_VERSION;
+}
+
+impl Game {
+	pub fn to_save_string(&self) -> String {
+		return versioned::to_json(self)
+	}
+
+	pub fn from_save_str(s: &str) -> Result<Game, SaveError> {
+		let game = versioned::from_json(s)?;
+		check_saved_game(&game)?;
+		return Ok(game)
+	}
+
+	// writes the game to the given path, creating its parent directory if needed
+	pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+		return versioned::save(self, path)
+	}
+
+	pub fn load(path: &Path) -> Result<Game, SaveError> {
+		let game = versioned::load(path)?;
+		check_saved_game(&game)?;
+		return Ok(game)
+	}
+}
+
+// catches save files that parse fine but have been edited (or truncated) into nonsense, including
+// histories that don't lead to the board, which undo would otherwise trip over
+fn check_saved_game(game: &Game) -> Result<(), SaveError> {
+	game.validate().map_err(|err| SaveError::Corrupt(err.to_string()))?;
+	return game.validate_history().map_err(|err| SaveError::Corrupt(err.to_string()))
+}
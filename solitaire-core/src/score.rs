@@ -0,0 +1,131 @@
+//! Scoring, in either the Standard (Windows-style) or Vegas rule set.
+//!
+//! Standard scoring:
+//! - waste to pile: 5, waste to foundation: 10, pile to foundation: 10
+//! - turning over a hidden card in a pile: 5
+//! - foundation to pile: -15
+//! - recycling the waste: -100 in Draw-1, or -20 for every pass after the third in Draw-3
+//! - 2 points lost for every 10 seconds played, and a bonus of 700,000 / seconds for winning in over 30 seconds
+//! - the score never drops below zero
+//!
+//! Vegas scoring is in dollars: every game costs $52 to play, and each card put on a foundation wins
+//! $5 back (and loses it again if it's taken back off). The balance carries over from one Vegas game
+//! to the next.
+//!
+//! Points are worked out from each move as it's made, and the score before every move is kept
+//! alongside the move history so that undoing a move restores the score exactly.
+
+use crate::game::{DrawCount, Game, GameStatus, Move, MoveDest, MoveSrc, Rules};
+use serde::{Deserialize, Serialize};
+
+const TIME_PENALTY_INTERVAL: u64 = 10; // seconds
+const TIME_PENALTY: i32 = 2;
+const TIME_BONUS_NUMERATOR: u64 = 700_000;
+const TIME_BONUS_MIN_SECS: u64 = 30; // no bonus is given for games won quicker than this
+const VEGAS_ANTE: i32 = 52;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum Scoring {
+	#[default]
+	Standard,
+	Vegas,
+}
+
+impl Scoring {
+	// the score a game starts with, before any moves are made
+	pub fn initial_points(&self) -> i32 {
+		return match self {
+			Scoring::Standard => 0,
+			Scoring::Vegas => -VEGAS_ANTE,
+		}
+	}
+
+	// the points won (or lost) by making the given move. `passes` is the number of passes through the
+	// stock after the move was made.
+	pub fn move_points(&self, rules: &Rules, mv: Move, passes: u32) -> i32 {
+		return match self {
+			Scoring::Standard => match mv {
+				Move::CardMove{ src, dest, .. } => {
+					let reveal = match src {
+						MoveSrc::FromPile{ reveals_hidden_card: true, .. } => 5,
+						_ => 0,
+					};
+					let points = match (src, dest) {
+						(MoveSrc::FromStock, MoveDest::ToPile(_)) => 5,
+						(MoveSrc::FromStock, MoveDest::ToFoundation(_)) => 10,
+						(MoveSrc::FromPile{..}, MoveDest::ToFoundation(_)) => 10,
+						(MoveSrc::FromPile{..}, MoveDest::ToPile(_)) => 0,
+						(MoveSrc::FromFoundation(_), _) => -15,
+					};
+					points + reveal
+				}
+				Move::DrawStock{..} => 0,
+				Move::RecycleWaste => match rules.draw_count {
+					DrawCount::One => -100,
+					DrawCount::Three if passes > 3 => -20,
+					DrawCount::Three => 0,
+				},
+			},
+			Scoring::Vegas => match mv {
+				Move::CardMove{ src: MoveSrc::FromFoundation(_), .. } => -5,
+				Move::CardMove{ dest: MoveDest::ToFoundation(_), .. } => 5,
+				Move::CardMove{..} | Move::DrawStock{..} | Move::RecycleWaste => 0,
+			},
+		}
+	}
+
+	// applies the rule set's lower limit (if any) to a score
+	fn clamp(&self, score: i32) -> i32 {
+		return match self {
+			Scoring::Standard => score.max(0),
+			Scoring::Vegas => score,
+		}
+	}
+}
+
+impl Game {
+	// this game's score, including any adjustments for the time taken
+	pub fn score(&self) -> i32 {
+		let scoring = self.rules.scoring;
+		return match scoring {
+			Scoring::Standard => {
+				let secs = self.elapsed as u64;
+				let mut score = self.points - (secs / TIME_PENALTY_INTERVAL) as i32 * TIME_PENALTY;
+				if self.status() == GameStatus::Won && secs >= TIME_BONUS_MIN_SECS {
+					score += (TIME_BONUS_NUMERATOR / secs) as i32;
+				}
+				scoring.clamp(score)
+			}
+			Scoring::Vegas => self.points,
+		}
+	}
+
+	// the score including the balance carried over from earlier games (Vegas only)
+	pub fn total_score(&self) -> i32 {
+		return self.carried_score + self.score()
+	}
+
+	// the score as it's shown to the player, eg. "Score: 120" or "Vegas: -$37"
+	pub fn score_text(&self) -> String {
+		return match self.rules.scoring {
+			Scoring::Standard => format!("Score: {}", self.score()),
+			Scoring::Vegas => {
+				let total = self.total_score();
+				let sign = if total < 0 { "-" } else { "" };
+				format!("Vegas: {}${}", sign, total.abs())
+			}
+		}
+	}
+
+	// updates the points after a move has been made (and added to the move history)
+	pub(crate) fn score_move(&mut self, mv: Move) {
+		let scoring = self.rules.scoring;
+		self.points = scoring.clamp(self.points + scoring.move_points(&self.rules, mv, self.passes));
+	}
+}
+
+// formats a number of seconds as minutes and seconds, eg. 2:05
+pub fn time_text(secs: f64) -> String {
+	let secs = secs as u64;
+	return format!("{}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,183 @@
+//! An exhaustive depth-first solver for klondike deals.
+//!
+//! The solver explores every reachable position (remembering the ones it's already seen in a
+//! transposition table, so each is only expanded once) until it either finds a winning line or runs
+//! out of positions, which proves that the deal can't be won. Since a full search can take a long
+//! time on hard deals, it gives up once it's exceeded its node or time budget.
+
+use crate::card::{Card, Rank, Suit};
+use crate::game::{DrawCount, Game, Move, MoveDest, MoveSrc, PassLimit};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SolverConfig {
+	pub max_nodes: usize, // the maximum number of positions to expand before giving up
+	pub time_limit: Option<Duration>, // the maximum wall-clock time to spend before giving up
+}
+
+impl Default for SolverConfig {
+	fn default() -> SolverConfig {
+		return SolverConfig {
+			max_nodes: 2_000_000,
+			time_limit: Some(Duration::from_secs(10)),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub enum SolveResult {
+	Solved(Vec<Move>), // the moves that win the game, in order, starting from the given position
+	Unsolvable, // every reachable position was searched and none of them are a win
+	GaveUp, // the budget ran out before the search finished
+}
+
+#[derive(Clone, Debug)]
+pub struct Solution {
+	pub result: SolveResult,
+	pub nodes: usize, // the number of positions that were expanded
+}
+
+// searches for a way to win the given game, starting from its current position.
+pub fn solve(game: &Game, config: SolverConfig) -> Solution {
+	let started_at = Instant::now();
+	let mut game = game.clone();
+	game.move_in_progress = None;
+	let history_start = game.history.len();
+
+	let mut seen = HashSet::new();
+	seen.insert(position_key(&game));
+
+	// each frame holds the moves that still need trying from one position along the current line.
+	// the move that led from each frame to the next is the matching entry in the game's history.
+	let mut stack = vec![ordered_moves(&game)];
+	let mut nodes = 1;
+
+	loop {
+		if game.is_won() {
+			let moves = game.history[history_start..].iter().map(|entry| entry.mv).collect();
+			return Solution { result: SolveResult::Solved(moves), nodes }
+		}
+
+		let frame = match stack.last_mut() {
+			Some(frame) => frame,
+			None => return Solution { result: SolveResult::Unsolvable, nodes },
+		};
+
+		match frame.pop() {
+			Some(mv) => {
+				if !game.exec_move(mv) {
+					continue
+				}
+				if !seen.insert(position_key(&game)) {
+					game.undo_move();
+					continue
+				}
+
+				nodes += 1;
+				if nodes >= config.max_nodes || config.time_limit.is_some_and(|limit| nodes % 1024 == 0 && started_at.elapsed() > limit) {
+					return Solution { result: SolveResult::GaveUp, nodes }
+				}
+				stack.push(ordered_moves(&game));
+			}
+			None => {
+				// every move from this position has been tried, so back up to the previous one
+				stack.pop();
+				if !stack.is_empty() {
+					game.undo_move();
+				}
+			}
+		}
+	}
+}
+
+// returns the moves worth trying from the current position, with the most promising move last
+// (since the solver pops moves off the end).
+fn ordered_moves(game: &Game) -> Vec<Move> {
+	let moves = game.legal_moves();
+
+	// a move to the foundation that can never block anything is always part of some winning line (if
+	// there is one), so there's no need to try anything else.
+	if let Some(mv) = moves.iter().find(|mv| is_safe_foundation_move(game, mv)) {
+		return vec![*mv]
+	}
+
+	let mut moves: Vec<Move> = moves.into_iter().filter(|mv| !is_pointless(game, mv)).collect();
+	moves.sort_by_key(move_priority);
+	return moves
+}
+
+// a card is safe to put on its foundation if it's an ace or a two, or if both foundations of the
+// opposite colour are already up to the rank below it (so nothing could ever need to go onto it).
+// playing from the waste in draw-3 changes which cards turn up later, so that's never assumed safe.
+fn is_safe_foundation_move(game: &Game, mv: &Move) -> bool {
+	let card = match mv {
+		Move::CardMove{ card, dest: MoveDest::ToFoundation(_), src: MoveSrc::FromPile{..} } => *card,
+		Move::CardMove{ card, dest: MoveDest::ToFoundation(_), src: MoveSrc::FromStock } if game.rules.draw_count == DrawCount::One => *card,
+		_ => return false,
+	};
+	if card.rank.index() <= Rank::Two.index() {
+		return true
+	}
+	return Suit::all().iter()
+		.filter(|suit| Card::new(**suit, card.rank).colour() != card.colour())
+		.all(|suit| game.foundation_fill_levels.get(suit).is_some_and(|rank| rank.index() >= card.rank.index() - 1))
+}
+
+// moving an entire pile (with nothing hidden underneath) into an empty pile changes nothing that matters.
+pub(crate) fn is_pointless(game: &Game, mv: &Move) -> bool {
+	if let Move::CardMove{ src: MoveSrc::FromPile{ pile_index, target_card_index: 0, .. }, dest: MoveDest::ToPile(dest_pile_index), .. } = mv {
+		return game.piles[*pile_index].hidden.is_empty() && game.piles[*dest_pile_index].is_empty()
+	}
+	return false
+}
+
+// higher is tried first
+fn move_priority(mv: &Move) -> u8 {
+	return match mv {
+		Move::CardMove{ src: MoveSrc::FromPile{ reveals_hidden_card: true, .. }, .. } => 6,
+		Move::CardMove{ src: MoveSrc::FromPile{..}, dest: MoveDest::ToFoundation(_), .. } => 5,
+		Move::CardMove{ src: MoveSrc::FromStock, dest: MoveDest::ToFoundation(_), .. } => 4,
+		Move::CardMove{ src: MoveSrc::FromStock, dest: MoveDest::ToPile(_), .. } => 3,
+		Move::CardMove{ src: MoveSrc::FromPile{..}, dest: MoveDest::ToPile(_), .. } => 2,
+		Move::DrawStock{..} | Move::RecycleWaste => 1,
+		Move::CardMove{ src: MoveSrc::FromFoundation(_), .. } => 0,
+	}
+}
+
+// a compact key that's identical for positions that are equivalent as far as winning is concerned.
+// the order of the piles doesn't matter, so the piles are sorted.
+fn position_key(game: &Game) -> Vec<u8> {
+	let mut piles: Vec<Vec<u8>> = game.piles.iter().map(|pile| {
+		let mut key: Vec<u8> = pile.hidden.iter().map(card_byte).collect();
+		key.push(SEPARATOR);
+		key.extend(pile.visible.iter().map(card_byte));
+		key
+	}).collect();
+	piles.sort();
+
+	let mut key = Vec::with_capacity(128);
+	for suit in Suit::all() {
+		key.push(game.foundation_fill_levels.get(suit).map_or(SEPARATOR, |rank| rank.index() as u8));
+	}
+	key.extend(game.stock.iter().map(card_byte));
+	key.push(SEPARATOR);
+	key.extend(game.waste.iter().map(card_byte));
+	key.push(SEPARATOR);
+	for pile in piles {
+		key.extend(pile);
+		key.push(SEPARATOR);
+	}
+
+	// the number of passes only matters if it's limited
+	if let PassLimit::Passes(_) = game.rules.pass_limit {
+		key.extend(game.passes.to_le_bytes());
+	}
+	return key
+}
+
+const SEPARATOR: u8 = u8::MAX;
+
+fn card_byte(card: &Card) -> u8 {
+	return (card.suit.foundation_index() as u8) * 16 + card.rank.index() as u8
+}
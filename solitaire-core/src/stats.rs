@@ -0,0 +1,133 @@
+//! Statistics about every game played, kept separately for each rule variant.
+//!
+//! A game is recorded once: as a win as soon as it's won, or as a loss when it's abandoned for a new
+//! deal. Games that are abandoned before the first move aren't recorded at all.
+
+use crate::game::{DrawCount, Game};
+use crate::save::SaveError;
+use crate::score::Scoring;
+use crate::versioned::{self, Versioned};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const STATS_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+	pub variants: Vec<VariantStats>,
+}
+
+// the statistics for one combination of draw count and scoring
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VariantStats {
+	pub draw_count: DrawCount,
+	pub scoring: Scoring,
+	pub played: u32,
+	pub won: u32,
+	pub current_streak: u32, // the number of games won in a row, up to and including the most recent game
+	pub best_streak: u32,
+	pub fastest_win: Option<f64>, // seconds
+	pub fewest_moves: Option<u32>, // the fewest moves made in a winning game
+	pub total_score: i64, // the sum of the scores of every game played, for working out the average
+}
+
+impl VariantStats {
+	pub fn new(draw_count: DrawCount, scoring: Scoring) -> VariantStats {
+		return VariantStats {
+			draw_count,
+			scoring,
+			played: 0,
+			won: 0,
+			current_streak: 0,
+			best_streak: 0,
+			fastest_win: None,
+			fewest_moves: None,
+			total_score: 0,
+		}
+	}
+
+	pub fn average_score(&self) -> Option<f64> {
+		if self.played == 0 {
+			return None
+		}
+		return Some(self.total_score as f64 / self.played as f64)
+	}
+
+	// the fraction of games played that were won, from 0 to 1
+	pub fn win_rate(&self) -> Option<f64> {
+		if self.played == 0 {
+			return None
+		}
+		return Some(self.won as f64 / self.played as f64)
+	}
+
+	fn record(&mut self, game: &Game) {
+		self.played += 1;
+		self.total_score += game.score() as i64;
+		if game.is_won() {
+			self.won += 1;
+			self.current_streak += 1;
+			self.best_streak = self.best_streak.max(self.current_streak);
+			self.fastest_win = Some(self.fastest_win.map_or(game.elapsed, |fastest| fastest.min(game.elapsed)));
+			self.fewest_moves = Some(self.fewest_moves.map_or(game.moves, |fewest| fewest.min(game.moves)));
+		} else {
+			self.current_streak = 0;
+		}
+	}
+}
+
+impl Versioned for Stats {
+	const KEY: &'static str = "stats";
+	const VERSION: u32 = STATS_VERSION;
+}
+
+impl Stats {
+	// the statistics for the given variant (all zeroes if it's never been played)
+	pub fn variant(&self, draw_count: DrawCount, scoring: Scoring) -> VariantStats {
+		return self.variants.iter()
+			.find(|v| v.draw_count == draw_count && v.scoring == scoring)
+			.copied()
+			.unwrap_or_else(|| VariantStats::new(draw_count, scoring))
+	}
+
+	// records the result of a game that has been won or abandoned. Returns false (and records
+	// nothing) if the game has already been recorded, or hadn't been started.
+	pub fn record(&mut self, game: &mut Game) -> bool {
+		if game.stats_recorded || !game.has_started() {
+			return false
+		}
+		game.stats_recorded = true;
+
+		let (draw_count, scoring) = (game.rules.draw_count, game.rules.scoring);
+		let index = match self.variants.iter().position(|v| v.draw_count == draw_count && v.scoring == scoring) {
+			Some(index) => index,
+			None => {
+				self.variants.push(VariantStats::new(draw_count, scoring));
+				self.variants.len() - 1
+			}
+		};
+		self.variants[index].record(game);
+		return true
+	}
+
+	pub fn reset(&mut self) {
+		self.variants.clear();
+	}
+
+	pub fn to_json(&self) -> String {
+		return versioned::to_json(self)
+	}
+
+	pub fn from_json(s: &str) -> Result<Stats, SaveError> {
+		return versioned::from_json(s)
+	}
+
+	// writes the stats to the given path, creating its parent directory if needed
+	pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+		return versioned::save(self, path)
+	}
+
+	pub fn load(path: &Path) -> Result<Stats, SaveError> {
+		return versioned::load(path)
+	}
+}
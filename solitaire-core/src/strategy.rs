@@ -0,0 +1,78 @@
+//! Strategies for playing a game automatically, one move at a time.
+//!
+//! A strategy is shown the game and the moves that can be made in it, and picks one. Bots can be
+//! written (and compared against each other, eg. with `solitaire-sim`) by implementing `Strategy`,
+//! without having to change `Game` itself.
+
+use crate::game::{Game, Move, MoveDest, MoveSrc};
+use crate::rng::Rng;
+use std::cmp::Reverse;
+
+pub trait Strategy {
+	// picks one of the given moves, which are all legal in the game's current position. There's
+	// always at least one move to choose from.
+	fn choose(&mut self, game: &Game, moves: &[Move]) -> Move;
+}
+
+impl Game {
+	// makes the move the strategy chooses. Returns false if there's no move to make, or the strategy
+	// chooses a move that can't be made.
+	pub fn play_strategy(&mut self, strategy: &mut dyn Strategy) -> bool {
+		let moves = self.legal_moves();
+		if moves.is_empty() {
+			return false
+		}
+		let mv = strategy.choose(self, &moves);
+		return self.exec_move(mv)
+	}
+}
+
+// the strategy used by Game::auto_move: moves that make progress first (turning over hidden cards
+// or emptying piles), then moves to the foundations, then moves from the waste, and otherwise it
+// goes through the stock. Cards only come back off the foundations when there's nothing else to do.
+pub struct Greedy;
+
+impl Strategy for Greedy {
+	fn choose(&mut self, _game: &Game, moves: &[Move]) -> Move {
+		// the first move in the list is the highest quality move. The sort is stable, so moves of
+		// the same quality are tried in the order they're given
+		let mut moves = moves.to_vec();
+		moves.sort_by_key(|mv| match mv {
+			// moves that make progress, with the most progress first
+			Move::CardMove{ src: MoveSrc::FromPile{ progress_value, .. }, .. } if *progress_value > 0 => (0, Reverse(*progress_value)),
+
+			// otherwise prefer moves to the foundation
+			Move::CardMove{ dest: MoveDest::ToFoundation(_), .. } => (1, Reverse(0)),
+
+			// then moves from stock to piles
+			Move::CardMove{ src: MoveSrc::FromStock, dest: MoveDest::ToPile(..), .. } => (2, Reverse(0)),
+
+			// then just cycle the stock
+			Move::DrawStock{..} | Move::RecycleWaste => (3, Reverse(0)),
+
+			// shuffling cards between piles doesn't get anywhere
+			Move::CardMove{ src: MoveSrc::FromPile{..}, .. } => (4, Reverse(0)),
+
+			// taking cards back off the foundations is a last resort
+			Move::CardMove{ src: MoveSrc::FromFoundation(_), .. } => (5, Reverse(0)),
+		});
+		return moves[0]
+	}
+}
+
+// picks any move at all, for a baseline to compare other strategies against
+pub struct RandomMoves {
+	rng: Rng,
+}
+
+impl RandomMoves {
+	pub fn new(seed: u64) -> RandomMoves {
+		return RandomMoves { rng: Rng::new(seed) }
+	}
+}
+
+impl Strategy for RandomMoves {
+	fn choose(&mut self, _game: &Game, moves: &[Move]) -> Move {
+		return moves[self.rng.gen_below(moves.len() as u64) as usize]
+	}
+}
@@ -0,0 +1,178 @@
+//! Checks that a game is in a state that could actually come up in play.
+//!
+//! Moves are trusted to be legal when they're made, so a bug in move generation or undo could quietly
+//! leave the board in an impossible state (a card lost or duplicated, a broken run in a pile). In
+//! debug builds the game is validated after every move and undo, so that such bugs are caught where
+//! they happen rather than several moves later.
+
+use crate::card::{Card, Suit};
+use crate::game::{Game, Move, MoveDest, MoveSrc, PassLimit, N_PILES};
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+	WrongNumberOfPiles(usize),
+	DuplicateCard(Card),
+	AlreadyOnFoundation(Card), // a card that's somewhere else as well as on its foundation
+	MissingCard(Card),
+	IllegalRun{ pile_index: usize, card: Card, onto: Card }, // a face up card that can't be piled onto the one below it
+	UncoveredHiddenCards{ pile_index: usize }, // a pile with face down cards but nothing face up on top
+	TooManyPasses{ passes: u32, limit: u32 },
+	ImpossibleMove{ move_index: usize }, // a move in the history that doesn't fit the board, eg. in an edited save
+	ImpossibleRedo{ redo_index: usize }, // a move waiting to be redone that couldn't be made
+}
+
+impl fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return match self {
+			ValidationError::WrongNumberOfPiles(n) => write!(f, "expected {} piles, found {}", N_PILES, n),
+			ValidationError::DuplicateCard(card) => write!(f, "{} appears more than once", card),
+			ValidationError::AlreadyOnFoundation(card) => write!(f, "{} is on its foundation, but also appears elsewhere", card),
+			ValidationError::MissingCard(card) => write!(f, "{} is missing", card),
+			ValidationError::IllegalRun{ pile_index, card, onto } => write!(f, "{} can't be on top of {} in pile {}", card, onto, pile_index + 1),
+			ValidationError::UncoveredHiddenCards{ pile_index } => write!(f, "pile {} has face down cards with no face up card on top", pile_index + 1),
+			ValidationError::TooManyPasses{ passes, limit } => write!(f, "pass {} is past the pass limit of {}", passes, limit),
+			ValidationError::ImpossibleMove{ move_index } => write!(f, "move {} in the history couldn't have been made", move_index + 1),
+			ValidationError::ImpossibleRedo{ redo_index } => write!(f, "move {} waiting to be redone couldn't be made", redo_index + 1),
+		}
+	}
+}
+
+impl std::error::Error for ValidationError {}
+
+impl Game {
+	// checks that every card in the deck appears exactly once, that the face up cards in each pile
+	// form a proper run and that the stock hasn't been gone through more times than the rules allow
+	pub fn validate(&self) -> Result<(), ValidationError> {
+		if self.piles.len() != N_PILES as usize {
+			return Err(ValidationError::WrongNumberOfPiles(self.piles.len()))
+		}
+
+		// each card in the deck, and whether it's been seen yet
+		let mut seen = [false; 52];
+		let card_index = |card: Card| card.suit.foundation_index()*13 + card.rank.index() as usize;
+
+		for suit in Suit::all() {
+			if let Some(top) = self.foundation_top_card(*suit) {
+				for card in Card::all_cards().iter().filter(|card| card.suit == *suit && card.rank.index() <= top.rank.index()) {
+					seen[card_index(*card)] = true;
+				}
+			}
+		}
+
+		let elsewhere = self.stock.iter()
+			.chain(self.waste.iter())
+			.chain(self.piles.iter().flat_map(|pile| pile.hidden.iter().chain(pile.visible.iter())));
+		for card in elsewhere {
+			if seen[card_index(*card)] {
+				let on_foundation = self.foundation_top_card(card.suit).is_some_and(|top| card.rank.index() <= top.rank.index());
+				return Err(match on_foundation {
+					true => ValidationError::AlreadyOnFoundation(*card),
+					false => ValidationError::DuplicateCard(*card),
+				})
+			}
+			seen[card_index(*card)] = true;
+		}
+
+		if let Some(card) = Card::all_cards().iter().find(|card| !seen[card_index(**card)]) {
+			return Err(ValidationError::MissingCard(*card))
+		}
+
+		for (pile_index, pile) in self.piles.iter().enumerate() {
+			if pile.visible.is_empty() && !pile.hidden.is_empty() {
+				return Err(ValidationError::UncoveredHiddenCards{ pile_index })
+			}
+			for pair in pile.visible.windows(2) {
+				if !pair[1].can_pile_onto(pair[0]) {
+					return Err(ValidationError::IllegalRun{ pile_index, card: pair[1], onto: pair[0] })
+				}
+			}
+		}
+
+		if let PassLimit::Passes(limit) = self.rules.pass_limit {
+			if self.passes > limit {
+				return Err(ValidationError::TooManyPasses{ passes: self.passes, limit })
+			}
+		}
+
+		return Ok(())
+	}
+
+	// checks that the history really leads to the current position: each move is taken back in turn
+	// (checking that the board is valid at every step), and then they're all made again from the
+	// start, checking that each one was legal and scored the same. Then checks that the moves waiting
+	// to be redone could be made. Undo and redo trust the history, so a save file has to pass this
+	// before it's played.
+	pub fn validate_history(&self) -> Result<(), ValidationError> {
+		let mut start = self.clone();
+		for (move_index, entry) in self.history.iter().enumerate().rev() {
+			if !start.can_unmove(entry.mv) {
+				return Err(ValidationError::ImpossibleMove{ move_index })
+			}
+			start.unmove_cards(entry.mv);
+			start.history.pop();
+			if start.validate().is_err() || start.position_hash() != entry.position_hash {
+				return Err(ValidationError::ImpossibleMove{ move_index })
+			}
+		}
+
+		let mut game = start;
+		game.redo_stack.clear();
+		if let Some(first) = self.history.first() {
+			game.points = first.points;
+		}
+		for (move_index, entry) in self.history.iter().enumerate() {
+			if game.points != entry.points || !game.exec_move(entry.mv) {
+				return Err(ValidationError::ImpossibleMove{ move_index })
+			}
+		}
+		if game.points != self.points {
+			return Err(ValidationError::ImpossibleMove{ move_index: self.history.len().saturating_sub(1) })
+		}
+
+		// the most recently undone move is redone first
+		for (redo_index, mv) in self.redo_stack.iter().enumerate().rev() {
+			if !game.exec_move(*mv) {
+				return Err(ValidationError::ImpossibleRedo{ redo_index })
+			}
+		}
+		return Ok(())
+	}
+
+	// returns true if the cards are where the move would have left them, so that it can be taken back
+	// without panicking
+	fn can_unmove(&self, mv: Move) -> bool {
+		return match mv {
+			Move::CardMove{ card, src, dest } => {
+				let src_ok = match src {
+					MoveSrc::FromStock => true,
+					MoveSrc::FromFoundation(suit) => suit == card.suit && matches!(dest, MoveDest::ToPile(_)),
+					MoveSrc::FromPile{ pile_index, n_cards, .. } => pile_index < self.piles.len() && n_cards >= 1,
+				};
+				let n_cards = match src {
+					MoveSrc::FromPile{ n_cards, .. } => n_cards as usize,
+					_ => 1,
+				};
+				let dest_ok = match dest {
+					MoveDest::ToPile(pile_index) => match self.piles.get(pile_index) {
+						Some(pile) => pile.visible.len() >= n_cards && pile.visible[pile.visible.len() - n_cards] == card,
+						None => false,
+					},
+					MoveDest::ToFoundation(suit) => n_cards == 1 && self.foundation_top_card(suit) == Some(card),
+				};
+				src_ok && dest_ok
+			}
+			Move::DrawStock{ n_cards } => n_cards >= 1 && self.waste.len() >= n_cards as usize,
+			Move::RecycleWaste => self.waste.is_empty() && self.passes > 1,
+		}
+	}
+
+	// in debug builds, panics if making (or undoing) the given move has left the game in an impossible state
+	pub(crate) fn debug_validate(&self, mv: Move) {
+		if cfg!(debug_assertions) {
+			if let Err(err) = self.validate() {
+				panic!("the game is invalid after {} ({:?}): {}\n{}", mv, mv, err, self);
+			}
+		}
+	}
+}
@@ -0,0 +1,53 @@
+//! Reading and writing the JSON files kept on disk (saved games, statistics and replays).
+//!
+//! Each file holds a version number alongside its contents, eg. `{"version":1,"stats":{...}}`. The
+//! version is read on its own first, so that the error for a file from an incompatible version
+//! doesn't depend on what else has changed.
+
+use crate::save::SaveError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+// something that's kept on disk in its own file
+pub(crate) trait Versioned: Serialize + DeserializeOwned {
+	const KEY: &'static str; // the name the contents are stored under, alongside the version
+	const VERSION: u32; // bump whenever the layout changes in a way that old files can't be read
+}
+
+pub(crate) fn to_json<T: Versioned>(value: &T) -> String {
+	let file = serde_json::json!({ "version": T::VERSION, T::KEY: value });
+	return serde_json::to_string(&file).expect("files on disk can always be serialised")
+}
+
+pub(crate) fn from_json<T: Versioned>(s: &str) -> Result<T, SaveError> {
+	let mut file: serde_json::Value = serde_json::from_str(s).map_err(|err| SaveError::Corrupt(err.to_string()))?;
+	let version = match file.get("version").and_then(|version| version.as_u64()) {
+		Some(version) => version as u32,
+		None => return Err(SaveError::Corrupt("there's no version number".to_string())),
+	};
+	if version != T::VERSION {
+		return Err(SaveError::IncompatibleVersion{ found: version, expected: T::VERSION })
+	}
+
+	let contents = match file.get_mut(T::KEY) {
+		Some(contents) => contents.take(),
+		None => return Err(SaveError::Corrupt(format!("there's no {}", T::KEY))),
+	};
+	return serde_json::from_value(contents).map_err(|err| SaveError::Corrupt(err.to_string()))
+}
+
+// writes the value to the given path, creating its parent directory if needed
+pub(crate) fn save<T: Versioned>(value: &T, path: &Path) -> Result<(), SaveError> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	fs::write(path, to_json(value))?;
+	return Ok(())
+}
+
+pub(crate) fn load<T: Versioned>(path: &Path) -> Result<T, SaveError> {
+	let s = fs::read_to_string(path)?;
+	return from_json(&s)
+}
@@ -0,0 +1,104 @@
+#![allow(clippy::needless_return)]
+
+use solitaire_core::{Cascade, CascadeLayout, Suit, CASCADE_STEPS_PER_SEC};
+
+fn layout() -> CascadeLayout {
+	return CascadeLayout {
+		width: 600.,
+		height: 500.,
+		card_w: 100.,
+		card_h: 140.,
+		foundations: [(255., 30.), (405., 30.), (555., 30.), (705., 30.)],
+	}
+}
+
+// runs a cascade to the end, one step at a time
+fn run_to_end(seed: u64) -> Cascade {
+	let mut cascade = Cascade::new(layout(), seed);
+	for _ in 0..CASCADE_STEPS_PER_SEC * 120 {
+		if cascade.is_finished() {
+			break
+		}
+		cascade.step();
+	}
+	return cascade
+}
+
+#[test]
+fn same_seed_gives_the_same_cascade() {
+	let mut a = Cascade::new(layout(), 42);
+	let mut b = Cascade::new(layout(), 42);
+	for _ in 0..1000 {
+		a.step();
+		b.step();
+	}
+	assert_eq!(a.cards(), b.cards());
+
+	let mut c = Cascade::new(layout(), 43);
+	for _ in 0..1000 {
+		c.step();
+	}
+	assert_ne!(a.cards(), c.cards());
+}
+
+#[test]
+fn frame_rate_does_not_change_the_outcome() {
+	let mut fast = Cascade::new(layout(), 7);
+	let mut slow = Cascade::new(layout(), 7);
+	let mut uneven = Cascade::new(layout(), 7);
+	for i in 0..600 {
+		fast.update(1. / 144.);
+		if i % 4 == 0 {
+			slow.update(4. / 144.);
+		}
+		uneven.update(if i % 2 == 0 { 0.5 / 144. } else { 1.5 / 144. });
+	}
+	assert_eq!(fast.steps(), slow.steps());
+	assert_eq!(fast.steps(), uneven.steps());
+	assert_eq!(fast.cards(), slow.cards());
+	assert_eq!(fast.cards(), uneven.cards());
+}
+
+#[test]
+fn cards_leave_highest_rank_first() {
+	let mut cascade = Cascade::new(layout(), 1);
+	for suit in Suit::all() {
+		assert_eq!(cascade.foundation_top(*suit).map(|rank| rank.index()), Some(12));
+	}
+
+	cascade.step();
+	let first = cascade.cards()[0].card;
+	assert_eq!(first.rank.index(), 12);
+	assert_eq!(first.suit.foundation_index(), 0);
+	assert_eq!(cascade.foundation_top(first.suit).map(|rank| rank.index()), Some(11));
+	let (x, y) = layout().foundations[0];
+	assert!((cascade.cards()[0].x - x).abs() < 5. && (cascade.cards()[0].y - y).abs() < 5.);
+}
+
+#[test]
+fn cards_never_fall_through_the_floor() {
+	let l = layout();
+	let mut cascade = Cascade::new(l, 3);
+	for _ in 0..CASCADE_STEPS_PER_SEC * 10 {
+		cascade.step();
+		for c in cascade.cards() {
+			assert!(c.y + l.card_h <= l.height + 0.001, "{:?} went through the floor", c.card);
+		}
+	}
+}
+
+#[test]
+fn every_card_eventually_leaves_the_screen() {
+	for seed in 0..10 {
+		let cascade = run_to_end(seed);
+		assert!(cascade.is_finished());
+		assert_eq!(cascade.cards().len(), 52);
+		for suit in Suit::all() {
+			assert_eq!(cascade.foundation_top(*suit), None);
+		}
+		let l = layout();
+		for c in cascade.cards() {
+			assert!(c.x + l.card_w < 0. || c.x > l.width);
+		}
+	}
+}
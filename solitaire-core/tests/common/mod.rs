@@ -0,0 +1,84 @@
+// positions and helpers shared between the test files. Each test file only uses some of them.
+#![allow(dead_code)]
+
+use solitaire_core::{DrawCount, Game, PassLimit, Rng, Rules};
+
+// nothing can move apart from the king in pile 1, back and forth between empty piles, and the stock
+// has been used up
+pub const STUCK: &str = "
+	draw: 1
+	pass limit: 1
+	scoring: standard
+	pass: 1
+	stock:
+	waste: 9C
+	foundations: - - - -
+	1: KD
+	2: AD 3D 4D 5D 6D 7D 8D 9D TD | 2S
+	3: JD QD AC 3C 4C 5C 6C 7C 8C | 2H
+	4: TC JC QC KC AH 3H 4H 5H 6H | 2C
+	5: 7H 8H 9H TH JH QH KH AS 3S | 2D
+	6: 5S 6S 7S 8S 9S TS JS QS KS | 4S
+	7:
+";
+
+// every card is on the foundations apart from the kings, which are face up in the first 4 piles
+pub const NEARLY_WON: &str = "
+	draw: 1
+	pass limit: unlimited
+	scoring: standard
+	pass: 1
+	stock:
+	waste:
+	foundations: QD QC QH QS
+	1: KD
+	2: KC
+	3: KH
+	4: KS
+	5:
+	6:
+	7:
+";
+
+// nearly won, but the clubs and hearts are under each other, so they have to go home in the right order
+pub const TANGLED: &str = "
+	draw: 1
+	pass limit: unlimited
+	scoring: standard
+	pass: 1
+	stock:
+	waste:
+	foundations: KD 6C 7H KS
+	1: KC QC JC TC 9C 8C | 8H 7C
+	2: QH JH TH 9H | KH
+	3:
+	4:
+	5:
+	6:
+	7:
+";
+
+// every combination of draw count and pass limit, with standard scoring
+pub fn all_rules() -> Vec<Rules> {
+	let mut rules = Vec::new();
+	for draw_count in [DrawCount::One, DrawCount::Three] {
+		for pass_limit in [PassLimit::Unlimited, PassLimit::Passes(1), PassLimit::Passes(3)] {
+			rules.push(Rules { draw_count, pass_limit, ..Rules::default() });
+		}
+	}
+	return rules
+}
+
+// makes up to `steps` random legal moves, stopping early if there aren't any, and calls `f` after
+// each one (which can undo moves, or change the game in other ways)
+pub fn play_random(game: &mut Game, rng: &mut Rng, steps: usize, mut f: impl FnMut(&mut Game, &mut Rng)) {
+	for _ in 0..steps {
+		let moves = game.legal_moves();
+		if moves.is_empty() {
+			break
+		}
+		let mv = moves[rng.gen_below(moves.len() as u64) as usize];
+		assert!(game.exec_move(mv), "legal move {:?} was rejected\n{}", mv, game);
+		f(game, rng);
+	}
+}
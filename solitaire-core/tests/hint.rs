@@ -0,0 +1,45 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{NEARLY_WON, STUCK, TANGLED};
+use solitaire_core::{hints, solve, Game, Move, MoveDest, Rules, SolveResult, SolverConfig};
+
+#[test]
+fn the_solvers_first_move_comes_first() {
+	let game = Game::from_position(TANGLED).unwrap();
+	let first = match solve(&game, SolverConfig::default()).result {
+		SolveResult::Solved(moves) => moves[0],
+		other => panic!("expected a solution, got {:?}", other),
+	};
+	assert_eq!(hints(&game).first(), Some(&first));
+}
+
+#[test]
+fn dead_positions_have_no_hints() {
+	let game = Game::from_position(STUCK).unwrap();
+	assert_eq!(hints(&game), vec![]);
+}
+
+#[test]
+fn kings_moving_between_empty_piles_arent_hinted() {
+	// each king could also go to any of the empty piles, but that doesn't get anywhere
+	let game = Game::from_position(NEARLY_WON).unwrap();
+	let to_empty_pile = |mv: &Move| matches!(mv, Move::CardMove{ dest: MoveDest::ToPile(pile_index), .. } if game.piles[*pile_index].is_empty());
+	assert!(game.legal_moves().iter().any(to_empty_pile));
+	let hints = hints(&game);
+	assert!(!hints.is_empty());
+	assert!(!hints.iter().any(to_empty_pile), "{:?}", hints);
+}
+
+#[test]
+fn hints_are_legal() {
+	for seed in 0..5 {
+		let game = Game::from_seed(seed, Rules::default());
+		let hints = hints(&game);
+		assert!(!hints.is_empty());
+		for mv in hints {
+			assert!(game.is_legal_move(mv), "{} on deal {}", mv, seed);
+		}
+	}
+}
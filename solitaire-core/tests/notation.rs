@@ -0,0 +1,106 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::play_random;
+use solitaire_core::{Card, DrawCount, Game, Move, MoveDest, MoveSrc, MoveText, MoveTextSrc, PassLimit, Rank, Rng, Rules, Scoring, Suit};
+
+// checks that the board notation reads back to the same position
+fn assert_board_round_trips(game: &Game) {
+	let text = game.to_string();
+	let parsed: Game = text.parse().unwrap_or_else(|err| panic!("{}\n{}", err, text));
+	assert_eq!(parsed.rules, game.rules);
+	assert_eq!(parsed.passes, game.passes);
+	assert_eq!(parsed.stock, game.stock);
+	assert_eq!(parsed.waste, game.waste);
+	assert_eq!(parsed.piles, game.piles);
+	assert_eq!(parsed.foundation_fill_levels, game.foundation_fill_levels);
+	assert_eq!(parsed.to_string(), text);
+}
+
+#[test]
+fn cards_round_trip() {
+	for card in Card::all_cards() {
+		let text = card.to_string();
+		assert_eq!(text.len(), 2);
+		assert_eq!(text.parse::<Card>(), Ok(*card));
+	}
+	assert_eq!("TD".parse::<Card>(), Ok(Card::new(Suit::Diamonds, Rank::Ten)));
+	assert_eq!("10d".parse::<Card>(), Ok(Card::new(Suit::Diamonds, Rank::Ten)));
+	assert_eq!("as".parse::<Card>(), Ok(Card::new(Suit::Spades, Rank::Ace)));
+	for bad in ["", "A", "1S", "AX", "ASS", "11S"] {
+		assert!(bad.parse::<Card>().is_err(), "{:?} parsed", bad);
+	}
+}
+
+#[test]
+fn moves_are_written_compactly() {
+	let ace = Card::new(Suit::Spades, Rank::Ace);
+	let from_pile = |pile_index, n_cards| MoveSrc::FromPile{ pile_index, n_cards, progress_value: 0, reveals_hidden_card: false, target_card_index: 0 };
+	assert_eq!(Move::CardMove{ card: ace, src: from_pile(2, 1), dest: MoveDest::ToFoundation(Suit::Spades) }.to_string(), "3>F");
+	assert_eq!(Move::CardMove{ card: ace, src: MoveSrc::FromStock, dest: MoveDest::ToPile(4) }.to_string(), "W>5");
+	assert_eq!(Move::CardMove{ card: ace, src: from_pile(5, 2), dest: MoveDest::ToPile(0) }.to_string(), "6:2>1");
+	assert_eq!(Move::CardMove{ card: ace, src: MoveSrc::FromFoundation(Suit::Hearts), dest: MoveDest::ToPile(1) }.to_string(), "FH>2");
+	assert_eq!(Move::DrawStock{ n_cards: 3 }.to_string(), "D");
+	assert_eq!(Move::RecycleWaste.to_string(), "R");
+}
+
+#[test]
+fn moves_round_trip_through_random_games() {
+	for seed in 0..20 {
+		let mut rng = Rng::new(seed);
+		let mut game = Game::from_seed(seed, Rules::default());
+		let check = |game: &mut Game, _: &mut Rng| {
+			for mv in game.legal_moves() {
+				assert_eq!(game.parse_move(&mv.to_string()), Ok(mv), "seed {} move {}", seed, mv);
+			}
+		};
+		check(&mut game, &mut rng);
+		play_random(&mut game, &mut rng, 150, check);
+	}
+}
+
+#[test]
+fn moves_parse_without_a_position() {
+	assert_eq!("3:2>1".parse(), Ok(MoveText::CardMove{ src: MoveTextSrc::Pile{ pile_index: 2, n_cards: 2 }, to_pile: Some(0) }));
+	assert_eq!("fh>f".parse(), Ok(MoveText::CardMove{ src: MoveTextSrc::Foundation(Suit::Hearts), to_pile: None }));
+	for text in ["W>5", "3>F", "6:2>1", "FH>2", "D", "R"] {
+		assert_eq!(text.parse::<MoveText>().unwrap().to_string(), text);
+	}
+	for bad in ["", "X", "3", "3>", ">3", "0>1", "8>1", "1>8", "3:0>1", "FX>1", "FSS>1", "W>W"] {
+		assert!(bad.parse::<MoveText>().is_err(), "{:?} parsed", bad);
+	}
+}
+
+#[test]
+fn illegal_or_malformed_moves_are_rejected() {
+	let game = Game::from_seed(1, Rules::default());
+	for bad in ["", "X", "3", "3>", ">3", "0>1", "8>1", "1>8", "3:0>1", "3:2>1", "FX>1", "FSS>1", "W>W", "R"] {
+		assert!(game.parse_move(bad).is_err(), "{:?} parsed", bad);
+	}
+}
+
+#[test]
+fn boards_round_trip() {
+	let rules = Rules { draw_count: DrawCount::Three, pass_limit: PassLimit::Passes(3), scoring: Scoring::Vegas };
+	assert_board_round_trips(&Game::from_seed(7, rules));
+
+	for seed in 0..10 {
+		let mut rng = Rng::new(seed);
+		let mut game = Game::from_seed(seed, Rules::default());
+		play_random(&mut game, &mut rng, 200, |game, _| assert_board_round_trips(game));
+	}
+}
+
+#[test]
+fn malformed_boards_are_rejected() {
+	let good = Game::from_seed(3, Rules::default()).to_string();
+	assert!(good.parse::<Game>().is_ok());
+
+	let without_pile_7: String = good.lines().filter(|line| !line.starts_with("7:")).map(|line| format!("{}\n", line)).collect();
+	assert!(without_pile_7.parse::<Game>().is_err());
+	assert!(good.replace("draw: 1", "draw: 2").parse::<Game>().is_err());
+	assert!(good.replace("foundations: - - - -", "foundations: AS - - -").parse::<Game>().is_err());
+	assert!(good.replace("foundations: - - - -", "foundations: - -").parse::<Game>().is_err());
+	assert!(format!("{}colour: red\n", good).parse::<Game>().is_err());
+}
@@ -0,0 +1,73 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{NEARLY_WON, STUCK};
+use solitaire_core::{Card, Game, GameStatus, PassLimit, PositionError, Rank, Rules, Suit, ValidationError};
+
+#[test]
+fn dealt_games_are_valid_positions() {
+	for seed in 0..20 {
+		let game = Game::from_seed(seed, Rules::default());
+		let loaded = Game::from_position(&game.to_string()).unwrap();
+		assert_eq!(loaded.piles, game.piles);
+		assert_eq!(loaded.stock, game.stock);
+		assert_eq!(loaded.seed, None);
+	}
+}
+
+#[test]
+fn a_loaded_position_can_be_played() {
+	let mut game = Game::from_position(NEARLY_WON).unwrap();
+	assert_eq!(game.status(), GameStatus::InProgress);
+	for pile in 1..=4 {
+		let mv = game.parse_move(&format!("{}>F", pile)).unwrap();
+		assert!(game.exec_move(mv));
+	}
+	assert_eq!(game.status(), GameStatus::Won);
+}
+
+#[test]
+fn a_position_with_only_pointless_moves_is_lost() {
+	let mut game = Game::from_position(STUCK).unwrap();
+	assert_eq!(game.legal_moves(), vec![game.parse_move("1>7").unwrap()]);
+	assert_eq!(game.status(), GameStatus::NoMovesRemaining);
+	assert!(!game.auto_move());
+}
+
+#[test]
+fn duplicate_and_missing_cards_are_rejected() {
+	let duplicate = NEARLY_WON.replace("5:", "5: KS");
+	assert!(matches!(Game::from_position(&duplicate), Err(PositionError::Invalid(ValidationError::DuplicateCard(card))) if card == Card::new(Suit::Spades, Rank::King)));
+
+	let missing = NEARLY_WON.replace("4: KS", "4:");
+	assert!(matches!(Game::from_position(&missing), Err(PositionError::Invalid(ValidationError::MissingCard(card))) if card == Card::new(Suit::Spades, Rank::King)));
+
+	let on_foundation = NEARLY_WON.replace("5:", "5: QS");
+	assert!(matches!(Game::from_position(&on_foundation), Err(PositionError::Invalid(ValidationError::AlreadyOnFoundation(card))) if card == Card::new(Suit::Spades, Rank::Queen)));
+}
+
+#[test]
+fn illegal_piles_are_rejected() {
+	let position = NEARLY_WON.replace("foundations: QD QC QH QS", "foundations: QD QC JH QS").replace("3: KH", "3: KH QH");
+	assert!(matches!(Game::from_position(&position), Err(PositionError::Invalid(ValidationError::IllegalRun{ pile_index: 2, .. }))));
+
+	let position = NEARLY_WON.replace("1: KD", "1: KD |");
+	assert!(matches!(Game::from_position(&position), Err(PositionError::Invalid(ValidationError::UncoveredHiddenCards{ pile_index: 0 }))));
+}
+
+#[test]
+fn passes_beyond_the_limit_are_rejected() {
+	let position = NEARLY_WON.replace("pass limit: unlimited", "pass limit: 3").replace("pass: 1", "pass: 4");
+	assert!(matches!(Game::from_position(&position), Err(PositionError::Invalid(ValidationError::TooManyPasses{ passes: 4, limit: 3 }))));
+	let position = NEARLY_WON.replace("pass limit: unlimited", "pass limit: 3").replace("pass: 1", "pass: 3");
+	assert_eq!(Game::from_position(&position).unwrap().rules.pass_limit, PassLimit::Passes(3));
+}
+
+#[test]
+fn syntax_errors_are_reported() {
+	let position = NEARLY_WON.replace("1: KD", "1: KX");
+	let err = Game::from_position(&position).unwrap_err();
+	assert!(matches!(err, PositionError::Syntax(_)));
+	assert!(err.to_string().contains("KX"));
+}
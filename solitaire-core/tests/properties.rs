@@ -0,0 +1,341 @@
+#![allow(clippy::needless_return)]
+
+// property tests for move generation and undo: random deals are played with random sequences of
+// legal moves, checking each move against the rules as it goes
+
+use proptest::prelude::*;
+use solitaire_core::{solve, Card, DrawCount, Game, GameStatus, MouseTarget, Move, MoveDest, MoveSrc, PassLimit, Rank, Rules, Scoring, SolveResult, SolverConfig, Suit, N_PILES};
+
+// every move that can be made by clicking on something in the current position
+fn clickable_moves(game: &Game) -> Vec<Move> {
+	let mut targets = vec![MouseTarget::StockDeck, MouseTarget::Recycle, MouseTarget::StockTop];
+	for suit in Suit::all() {
+		targets.push(MouseTarget::Foundation(*suit));
+	}
+	for (pile_index, pile) in game.piles.iter().enumerate() {
+		if pile.is_empty() {
+			targets.push(MouseTarget::EmptyPile(pile_index));
+		}
+		for (card_index, card) in pile.visible.iter().enumerate() {
+			targets.push(MouseTarget::PileCard{
+				pile_index,
+				n_cards: (pile.visible.len() - card_index) as u8,
+				target_card: *card,
+				target_card_index: card_index,
+				top: 0.,
+			});
+		}
+	}
+	return targets.into_iter().filter_map(|target| game.calc_moves(target)).flatten().collect()
+}
+
+// checks a move against the rules of klondike, independently of how legal_moves works them out
+fn obeys_the_rules(game: &Game, mv: Move) -> Result<(), String> {
+	let (card, src, dest) = match mv {
+		Move::DrawStock{ n_cards } => {
+			let expected = game.rules.draw_count.n_cards().min(game.stock.len());
+			return match n_cards as usize == expected && expected > 0 {
+				true => Ok(()),
+				false => Err(format!("drew {} cards with {} in the stock", n_cards, game.stock.len())),
+			}
+		}
+		Move::RecycleWaste => {
+			let allowed = game.stock.is_empty() && !game.waste.is_empty() && game.rules.pass_limit.allows_another_pass(game.passes);
+			return match allowed {
+				true => Ok(()),
+				false => Err("recycled the waste when it wasn't allowed".to_string()),
+			}
+		}
+		Move::CardMove{ card, src, dest } => (card, src, dest),
+	};
+
+	// the card has to be where the move says it is
+	let (src_pile, n_cards) = match src {
+		MoveSrc::FromStock => {
+			if game.waste.last() != Some(&card) {
+				return Err(format!("{} isn't on top of the waste", card))
+			}
+			(None, 1)
+		}
+		MoveSrc::FromFoundation(suit) => {
+			if suit != card.suit || game.foundation_top_card(suit) != Some(card) {
+				return Err(format!("{} isn't on top of its foundation", card))
+			}
+			(None, 1)
+		}
+		MoveSrc::FromPile{ pile_index, n_cards, reveals_hidden_card, target_card_index, .. } => {
+			let pile = &game.piles[pile_index];
+			if pile.visible.get(target_card_index) != Some(&card) || pile.visible.len() - target_card_index != n_cards as usize {
+				return Err(format!("{} cards from {} aren't the top of pile {}", n_cards, card, pile_index + 1))
+			}
+			if reveals_hidden_card != (target_card_index == 0 && !pile.hidden.is_empty()) {
+				return Err(format!("moving {} gets reveals_hidden_card wrong", card))
+			}
+			(Some(pile_index), n_cards)
+		}
+	};
+
+	// and it has to be allowed to go where it's going
+	match dest {
+		MoveDest::ToFoundation(suit) => {
+			let fits = match game.foundation_top_card(suit) {
+				Some(top) => card.can_stack_onto_in_foundation(top),
+				None => card.rank == Rank::Ace,
+			};
+			if suit != card.suit || n_cards != 1 || !fits {
+				return Err(format!("{} can't go on the {:?} foundation", card, suit))
+			}
+		}
+		MoveDest::ToPile(pile_index) => {
+			let fits = match game.piles[pile_index].top_card() {
+				Some(top) => card.can_pile_onto(top),
+				None => game.piles[pile_index].is_empty() && card.rank == Rank::King,
+			};
+			if src_pile == Some(pile_index) || !fits {
+				return Err(format!("{} can't go on pile {}", card, pile_index + 1))
+			}
+		}
+	}
+	return Ok(())
+}
+
+fn rules() -> impl Strategy<Value = Rules> {
+	let draw_count = prop_oneof![Just(DrawCount::One), Just(DrawCount::Three)];
+	let pass_limit = prop_oneof![Just(PassLimit::Unlimited), Just(PassLimit::Passes(1)), Just(PassLimit::Passes(3))];
+	let scoring = prop_oneof![Just(Scoring::Standard), Just(Scoring::Vegas)];
+	return (draw_count, pass_limit, scoring).prop_map(|(draw_count, pass_limit, scoring)| Rules { draw_count, pass_limit, scoring })
+}
+
+// each choice picks one of the available moves (modulo however many there are)
+fn choices() -> impl Strategy<Value = Vec<usize>> {
+	return prop::collection::vec(any::<usize>(), 0..150)
+}
+
+// any move at all, legal or not
+fn any_move() -> impl Strategy<Value = Move> {
+	let card = prop::sample::select(Card::all_cards().to_vec());
+	let suit = prop::sample::select(Suit::all().to_vec());
+	let pile_index = 0..N_PILES as usize;
+	let src = prop_oneof![
+		Just(MoveSrc::FromStock),
+		suit.clone().prop_map(MoveSrc::FromFoundation),
+		(pile_index.clone(), 1..14u8, 0..3usize, any::<bool>(), 0..13usize).prop_map(|(pile_index, n_cards, progress_value, reveals_hidden_card, target_card_index)| {
+			MoveSrc::FromPile{ pile_index, n_cards, progress_value, reveals_hidden_card, target_card_index }
+		}),
+	];
+	let dest = prop_oneof![suit.prop_map(MoveDest::ToFoundation), pile_index.prop_map(MoveDest::ToPile)];
+	return prop_oneof![
+		(0..4u8).prop_map(|n_cards| Move::DrawStock{ n_cards }),
+		Just(Move::RecycleWaste),
+		(card, src, dest).prop_map(|(card, src, dest)| Move::CardMove{ card, src, dest }),
+	]
+}
+
+// plays the chosen moves from a fresh deal, stopping early if there's nothing left to do
+fn play(seed: u64, rules: Rules, choices: &[usize]) -> Game {
+	let mut game = Game::from_seed(seed, rules);
+	for choice in choices {
+		let moves = game.legal_moves();
+		if moves.is_empty() {
+			break
+		}
+		game.exec_move(moves[choice % moves.len()]);
+	}
+	return game
+}
+
+// the game as it should be after a move and its undo: the same as before, apart from the counters
+// and the move waiting to be redone
+fn undone(mut game: Game, before: &Game) -> Game {
+	game.moves = before.moves;
+	game.undos = before.undos;
+	game.redo_stack = before.redo_stack.clone();
+	return game
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn legal_moves_obey_the_rules(seed in any::<u64>(), rules in rules(), choices in choices()) {
+		let mut game = Game::from_seed(seed, rules);
+		for choice in choices {
+			let moves = game.legal_moves();
+			if moves.is_empty() {
+				break
+			}
+			for mv in &moves {
+				if let Err(reason) = obeys_the_rules(&game, *mv) {
+					return Err(TestCaseError::fail(format!("{} ({:?}): {}\n{}", mv, mv, reason, game)))
+				}
+				prop_assert!(game.is_legal_move(*mv));
+				prop_assert!(game.clone().exec_move(*mv), "{} was offered but can't be made\n{}", mv, game);
+			}
+			game.exec_move(moves[choice % moves.len()]);
+			prop_assert_eq!(game.validate(), Ok(()));
+		}
+	}
+
+	#[test]
+	fn legal_moves_are_everything_calc_moves_offers(seed in any::<u64>(), rules in rules(), choices in choices()) {
+		let game = play(seed, rules, &choices);
+		prop_assert_eq!(game.legal_moves(), clickable_moves(&game), "{}", game);
+	}
+
+	#[test]
+	fn undo_restores_the_previous_state(seed in any::<u64>(), rules in rules(), choices in choices()) {
+		let mut game = Game::from_seed(seed, rules);
+		for choice in choices {
+			let moves = game.legal_moves();
+			if moves.is_empty() {
+				break
+			}
+			let before = game.clone();
+			let mv = moves[choice % moves.len()];
+			prop_assert!(game.exec_move(mv));
+			game.undo_move();
+			prop_assert_eq!(undone(game.clone(), &before), before.clone(), "undoing {} didn't restore the game", mv);
+			prop_assert_eq!(game.redo_stack.last(), Some(&mv));
+			prop_assert!(game.exec_move(mv));
+		}
+	}
+
+	#[test]
+	fn undoing_everything_returns_to_the_deal(seed in any::<u64>(), rules in rules(), choices in choices()) {
+		let mut game = play(seed, rules, &choices);
+		while !game.history.is_empty() {
+			game.undo_move();
+		}
+		let dealt = Game::from_seed(seed, rules);
+		prop_assert_eq!(undone(game, &dealt), dealt);
+	}
+
+	#[test]
+	fn illegal_moves_are_refused(seed in any::<u64>(), rules in rules(), choices in choices(), mv in any_move()) {
+		let mut game = play(seed, rules, &choices);
+		let legal = game.legal_moves();
+		let before = game.clone();
+		prop_assert_eq!(game.exec_move(mv), legal.contains(&mv), "{} ({:?})\n{}", mv, mv, before);
+		if !legal.contains(&mv) {
+			prop_assert_eq!(game, before);
+		}
+	}
+
+	#[test]
+	fn slightly_wrong_moves_are_refused(seed in any::<u64>(), rules in rules(), choices in choices(), pick in any::<usize>(), card in prop::sample::select(Card::all_cards().to_vec()), pile_index in 0..N_PILES as usize) {
+		// legal moves with one detail changed are much more likely to slip through than random moves
+		let mut game = play(seed, rules, &choices);
+		let legal = game.legal_moves();
+		prop_assume!(!legal.is_empty());
+		let variants = match legal[pick % legal.len()] {
+			Move::CardMove{ card: real_card, src, dest } => vec![
+				Move::CardMove{ card, src, dest },
+				Move::CardMove{ card: real_card, src, dest: MoveDest::ToPile(pile_index) },
+				Move::CardMove{ card: real_card, src, dest: MoveDest::ToFoundation(card.suit) },
+			],
+			Move::DrawStock{ n_cards } => vec![Move::DrawStock{ n_cards: n_cards + 1 }, Move::DrawStock{ n_cards: n_cards - 1 }],
+			Move::RecycleWaste => vec![],
+		};
+		for mv in variants {
+			let before = game.clone();
+			let applied = game.exec_move(mv);
+			prop_assert_eq!(applied, legal.contains(&mv), "{} ({:?})\n{}", mv, mv, before);
+			if applied {
+				game.undo_move();
+				game = undone(game, &before);
+			}
+			prop_assert_eq!(&game, &before);
+		}
+	}
+}
+
+#[test]
+fn cards_can_come_off_the_foundations() {
+	let game = Game::from_position("
+		draw: 1
+		pass limit: unlimited
+		scoring: standard
+		pass: 1
+		stock:
+		waste:
+		foundations: QD QC QH QS
+		1: KS
+		2: KC
+		3: KH
+		4: KD
+		5:
+		6:
+		7:
+	").unwrap();
+	let from_foundations: Vec<String> = game.legal_moves().iter()
+		.filter(|mv| matches!(mv, Move::CardMove{ src: MoveSrc::FromFoundation(_), .. }))
+		.map(|mv| mv.to_string())
+		.collect();
+	assert_eq!(from_foundations, ["FD>1", "FD>2", "FC>3", "FC>4", "FH>1", "FH>2", "FS>3", "FS>4"]);
+}
+
+#[test]
+fn kings_can_come_off_the_foundations_onto_empty_piles() {
+	// the hearts are stuck under the queen until a king comes back down for her to go onto
+	let mut game = Game::from_position("
+		draw: 1
+		pass limit: unlimited
+		scoring: standard
+		pass: 1
+		stock:
+		waste:
+		foundations: KD KC - KS
+		1: KH JH TH 9H 8H 7H 6H 5H 4H 3H 2H AH | QH
+		2:
+		3:
+		4:
+		5:
+		6:
+		7:
+	").unwrap();
+	let moves: Vec<String> = game.legal_moves().iter().map(|mv| mv.to_string()).collect();
+	// any of the three kings can go onto any of the six empty piles
+	assert_eq!(moves.len(), 18);
+	assert!(moves.iter().all(|mv| mv.starts_with('F')));
+	assert!(moves.contains(&"FS>7".to_string()));
+	assert_eq!(game.status(), GameStatus::InProgress);
+
+	let solution = match solve(&game, SolverConfig::default()).result {
+		SolveResult::Solved(moves) => moves,
+		other => panic!("expected a solution, got {:?}", other),
+	};
+	for mv in solution {
+		assert!(game.exec_move(mv), "{}", mv);
+	}
+	assert!(game.is_won());
+}
+
+// random play hardly ever gets a king onto a foundation, so the targets that random play rarely
+// reaches are checked on a fixed position as well: a king on a foundation, kings on the waste and in
+// the piles, and empty piles for them to go to
+#[test]
+fn calc_moves_agrees_with_legal_moves_with_kings_to_move() {
+	let game = Game::from_position("
+		draw: 1
+		pass limit: unlimited
+		scoring: standard
+		pass: 1
+		stock:
+		waste: KC
+		foundations: KD QC QH QS
+		1: KH
+		2: KS
+		3:
+		4:
+		5:
+		6:
+		7:
+	").unwrap();
+	let legal = game.legal_moves();
+	assert_eq!(legal, clickable_moves(&game));
+	assert!(legal.contains(&Move::RecycleWaste));
+	assert!(legal.iter().any(|mv| matches!(mv, Move::CardMove{ src: MoveSrc::FromStock, .. })));
+	assert!(legal.iter().any(|mv| matches!(mv, Move::CardMove{ src: MoveSrc::FromFoundation(Suit::Diamonds), dest: MoveDest::ToPile(_), .. })));
+	assert!(legal.iter().any(|mv| matches!(mv, Move::CardMove{ src: MoveSrc::FromPile{..}, dest: MoveDest::ToFoundation(_), .. })));
+	assert_eq!(game.calc_moves(MouseTarget::EmptyPile(2)), None);
+}
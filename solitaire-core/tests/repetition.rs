@@ -0,0 +1,120 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::NEARLY_WON;
+use solitaire_core::{solve, Game, GameStatus, PassLimit, Rules, SolveResult, SolverConfig, REPEAT_LIMIT};
+
+fn play(game: &mut Game, moves: &[&str]) {
+	for text in moves {
+		let mv = game.parse_move(text).unwrap();
+		assert!(game.exec_move(mv), "{}", text);
+	}
+}
+
+// draws every card in the stock then turns the waste back over
+fn cycle_stock(game: &mut Game) {
+	while !game.stock.is_empty() {
+		play(game, &["D"]);
+	}
+	play(game, &["R"]);
+}
+
+#[test]
+fn the_same_position_hashes_the_same() {
+	let game = Game::from_seed(4, Rules::default());
+	assert_eq!(game.position_hash(), Game::from_seed(4, Rules::default()).position_hash());
+	assert_ne!(game.position_hash(), Game::from_seed(5, Rules::default()).position_hash());
+
+	let mut moved = game.clone();
+	play(&mut moved, &["D"]);
+	assert_ne!(moved.position_hash(), game.position_hash());
+	moved.undo_move();
+	assert_eq!(moved.position_hash(), game.position_hash());
+	assert!(moved.history.is_empty());
+}
+
+#[test]
+fn shuffling_a_king_between_piles_loops() {
+	let mut game = Game::from_position(NEARLY_WON).unwrap();
+	for _ in 1..REPEAT_LIMIT {
+		play(&mut game, &["1>5", "5>1"]);
+	}
+	assert_eq!(game.times_seen(), REPEAT_LIMIT - 1);
+	assert!(!game.is_looping());
+
+	play(&mut game, &["1>5", "5>1"]);
+	assert_eq!(game.times_seen(), REPEAT_LIMIT);
+	assert!(game.is_looping());
+	assert!(!game.auto_move());
+
+	// undoing back out of the loop lets auto-move carry on
+	game.undo_move();
+	assert!(!game.is_looping());
+	assert!(game.auto_move());
+}
+
+#[test]
+fn cycling_the_stock_loops_with_unlimited_passes() {
+	let mut game = Game::from_seed(2, Rules::default());
+	for _ in 1..REPEAT_LIMIT {
+		cycle_stock(&mut game);
+	}
+	assert!(!game.is_looping());
+	cycle_stock(&mut game);
+	assert!(game.is_looping());
+}
+
+#[test]
+fn positions_part_way_through_the_stock_only_count_once_per_pass() {
+	// the first draw of every pass reaches the same position, but it's the pass that repeats
+	let mut game = Game::from_seed(2, Rules::default());
+	cycle_stock(&mut game);
+	cycle_stock(&mut game);
+	play(&mut game, &["D"]);
+	assert_eq!(game.times_seen(), 0);
+	assert!(!game.is_looping());
+}
+
+#[test]
+fn a_winnable_deal_can_be_won_after_looking_through_the_stock() {
+	// a player who looks through the stock a couple of times before playing hasn't got stuck
+	let start = Game::from_seed(3, Rules::default());
+	let solution = match solve(&start, SolverConfig::default()).result {
+		SolveResult::Solved(moves) => moves,
+		other => panic!("deal 3 should be winnable, got {:?}", other),
+	};
+	let mut game = start.clone();
+	for _ in 1..REPEAT_LIMIT {
+		cycle_stock(&mut game);
+	}
+	assert_eq!(game.position_hash(), start.position_hash());
+	assert!(game.clone().auto_move());
+	for mv in solution {
+		assert!(!game.is_looping(), "stopped before {}\n{}", mv, game);
+		assert!(game.exec_move(mv), "{}", mv);
+	}
+	assert_eq!(game.status(), GameStatus::Won);
+}
+
+#[test]
+fn cycling_the_stock_makes_progress_with_limited_passes() {
+	// each pass used up is a different position, as there are fewer left
+	let mut game = Game::from_seed(2, Rules { pass_limit: PassLimit::Passes(3), ..Rules::default() });
+	cycle_stock(&mut game);
+	cycle_stock(&mut game);
+	assert_eq!(game.times_seen(), 0);
+	assert!(!game.is_looping());
+}
+
+#[test]
+fn auto_move_always_stops() {
+	for seed in 0..20 {
+		let mut game = Game::from_seed(seed, Rules::default());
+		let mut n_moves = 0;
+		while game.auto_move() {
+			n_moves += 1;
+			assert!(n_moves < 5000, "auto-move kept going on deal {}", seed);
+		}
+	}
+}
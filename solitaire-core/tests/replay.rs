@@ -0,0 +1,125 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::play_random;
+use solitaire_core::{solve, Game, GameStatus, Move, PassLimit, RandomMoves, Replay, Replayer, Rng, Rules, SaveError, SolveResult, SolverConfig, TimedMove, REPLAY_VERSION};
+
+// plays a random game, with the odd undo thrown in, one second per move
+fn played_game(seed: u64) -> Game {
+	let mut rng = Rng::new(seed);
+	let mut game = Game::from_seed(seed, Rules::default());
+	play_random(&mut game, &mut rng, 120, |game, rng| {
+		game.elapsed += 1.;
+		if rng.gen_below(10) == 0 {
+			game.undo_move();
+		}
+	});
+	return game
+}
+
+#[test]
+fn replay_reaches_the_final_position() {
+	for seed in 0..20 {
+		let game = played_game(seed);
+		let mut replayer = Replayer::new(Replay::from_game(&game).unwrap()).unwrap();
+		replayer.seek(usize::MAX);
+		assert!(replayer.is_at_end());
+		assert_eq!(replayer.game().piles, game.piles);
+		assert_eq!(replayer.game().stock, game.stock);
+		assert_eq!(replayer.game().waste, game.waste);
+		assert_eq!(replayer.game().foundation_fill_levels, game.foundation_fill_levels);
+		assert_eq!(replayer.game().history, game.history);
+		assert_eq!(replayer.game().elapsed, game.history.last().map_or(0., |entry| entry.time));
+	}
+}
+
+#[test]
+fn stepping_back_returns_to_the_deal() {
+	let game = played_game(5);
+	let mut replayer = Replayer::new(Replay::from_game(&game).unwrap()).unwrap();
+	let start = replayer.game().clone();
+	while replayer.step_forward() {}
+	while replayer.step_back() {}
+	assert_eq!(replayer.position(), 0);
+	assert_eq!(replayer.game().piles, start.piles);
+	assert_eq!(replayer.game().stock, start.stock);
+	assert!(replayer.game().history.is_empty());
+}
+
+#[test]
+fn timestamps_are_kept() {
+	let game = played_game(3);
+	let replay = Replay::from_game(&game).unwrap();
+	assert_eq!(replay.moves.len(), game.history.len());
+	for pair in replay.moves.windows(2) {
+		assert!(pair[0].time <= pair[1].time);
+	}
+
+	let mut replayer = Replayer::new(replay.clone()).unwrap();
+	assert_eq!(replayer.time_to_next_move(), Some(replay.moves[0].time));
+	replayer.step_forward();
+	assert_eq!(replayer.time_to_next_move(), Some(replay.moves[1].time - replay.moves[0].time));
+}
+
+#[test]
+fn replays_round_trip_through_json() {
+	let replay = Replay::from_game(&played_game(8)).unwrap();
+	assert_eq!(Replay::from_json(&replay.to_json()).unwrap(), replay);
+
+	let wrong_version = replay.to_json().replacen(&format!("\"version\":{}", REPLAY_VERSION), "\"version\":0", 1);
+	assert!(matches!(Replay::from_json(&wrong_version), Err(SaveError::IncompatibleVersion{ found: 0, .. })));
+}
+
+#[test]
+fn impossible_moves_are_rejected() {
+	let mut replay = Replay::from_game(&played_game(2)).unwrap();
+	replay.moves.insert(0, replay.moves[0]);
+	replay.moves.insert(0, TimedMove{ mv: Move::RecycleWaste, time: 0. });
+	assert!(matches!(Replayer::new(replay), Err(SaveError::Corrupt(_))));
+}
+
+#[test]
+fn custom_deals_have_no_replay() {
+	let mut game = played_game(1);
+	game.seed = None;
+	assert!(Replay::from_game(&game).is_none());
+}
+
+#[test]
+fn a_replay_is_saved_once_the_game_is_won_after_being_stuck() {
+	// random play gets this deal stuck, but it can be won from the start
+	let rules = Rules { pass_limit: PassLimit::Passes(1), ..Rules::default() };
+	let mut game = Game::from_seed(870, rules);
+	let solution = match solve(&game, SolverConfig::default()).result {
+		SolveResult::Solved(moves) => moves,
+		other => panic!("deal 870 should be winnable, got {:?}", other),
+	};
+	let mut strategy = RandomMoves::new(870);
+	while game.status() == GameStatus::InProgress {
+		assert!(game.play_strategy(&mut strategy));
+	}
+	assert_eq!(game.status(), GameStatus::NoMovesRemaining);
+	assert!(Replay::to_save(&mut game, false).is_none());
+
+	while !game.history.is_empty() {
+		game.undo_move();
+	}
+	for mv in &solution {
+		assert!(game.exec_move(*mv), "{}", mv);
+	}
+	let replay = Replay::to_save(&mut game, false).unwrap();
+	assert_eq!(replay.moves.iter().map(|timed| timed.mv).collect::<Vec<Move>>(), solution);
+	assert!(Replay::to_save(&mut game, false).is_none());
+}
+
+#[test]
+fn abandoned_games_are_saved_but_unstarted_ones_arent() {
+	let mut game = played_game(4);
+	assert!(Replay::to_save(&mut game, false).is_none());
+	assert!(Replay::to_save(&mut game, true).is_some());
+	assert!(Replay::to_save(&mut game, true).is_none());
+
+	let mut unstarted = Game::from_seed(4, Rules::default());
+	assert!(Replay::to_save(&mut unstarted, true).is_none());
+}
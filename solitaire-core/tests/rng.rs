@@ -0,0 +1,42 @@
+#![allow(clippy::needless_return)]
+
+// deal numbers are shared between players, so these pin the generator and the deal it produces.
+// if either of these tests fails, every deal number out there now deals a different game.
+
+use solitaire_core::{Card, Game, Rng, Rules};
+
+fn cards(text: &str) -> Vec<Card> {
+	return text.split_whitespace().map(|card| card.parse().unwrap()).collect()
+}
+
+#[test]
+fn the_generator_is_splitmix64() {
+	// the reference outputs of splitmix64.c, seeded with 1234567
+	let mut rng = Rng::new(1234567);
+	let expected = [6457827717110365317, 3203168211198807973, 9817491932198370423, 4593380528125082431, 16408922859458223821];
+	for value in expected {
+		assert_eq!(rng.next_u64(), value);
+	}
+	assert_eq!(Rng::new(0).next_u64(), 16294208416658607535);
+}
+
+#[test]
+fn deal_1_never_changes() {
+	let game = Game::from_seed(1, Rules::default());
+	let piles = [
+		("", "8H"),
+		("TS", "5H"),
+		("9D 9H", "6C"),
+		("QH 7H TC", "QS"),
+		("6H AH 4S 3H", "QD"),
+		("6D 8D 5D 3S 2H", "7C"),
+		("AS AD JD JC QC JH", "9C"),
+	];
+	assert_eq!(game.piles.len(), piles.len());
+	for (pile, (hidden, visible)) in game.piles.iter().zip(piles) {
+		assert_eq!(pile.hidden, cards(hidden));
+		assert_eq!(pile.visible, cards(visible));
+	}
+	// the top of the stock is the last card
+	assert_eq!(game.stock, cards("8C TD 3C 4D JS 9S 3D 5S 2S 4C 4H TH KH KD KC 7D AC 2D 6S 2C 8S 7S 5C KS"));
+}
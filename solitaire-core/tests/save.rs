@@ -0,0 +1,89 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::play_random;
+use solitaire_core::{Game, Move, MoveDest, Rng, Rules, SaveError, SAVE_VERSION};
+
+// a game part way through, with some moves undone and waiting to be redone
+fn game_in_progress(seed: u64) -> Game {
+	let mut rng = Rng::new(seed);
+	let mut game = Game::from_seed(seed, Rules::default());
+	play_random(&mut game, &mut rng, 60, |game, _| game.elapsed += 1.);
+	game.undo_move();
+	game.undo_move();
+	return game
+}
+
+#[test]
+fn games_round_trip_mid_game() {
+	for seed in 0..10 {
+		let game = game_in_progress(seed);
+		let mut loaded = Game::from_save_str(&game.to_save_string()).unwrap();
+		assert_eq!(loaded, game);
+
+		// the history and redo stack still work after loading
+		let mut expected = game.clone();
+		loaded.undo_move();
+		expected.undo_move();
+		assert_eq!(loaded, expected);
+		assert!(loaded.redo_move());
+		assert!(expected.redo_move());
+		assert_eq!(loaded, expected);
+	}
+}
+
+#[test]
+fn games_round_trip_through_a_file() {
+	let game = game_in_progress(3);
+	let path = std::env::temp_dir().join(format!("solitaire-core-test-{}", std::process::id())).join("save.json");
+	game.save(&path).unwrap();
+	let loaded = Game::load(&path);
+	std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+	assert_eq!(loaded.unwrap(), game);
+}
+
+#[test]
+fn other_versions_are_incompatible() {
+	let save = game_in_progress(1).to_save_string();
+	let wrong_version = save.replacen(&format!("\"version\":{}", SAVE_VERSION), "\"version\":999", 1);
+	assert!(matches!(Game::from_save_str(&wrong_version), Err(SaveError::IncompatibleVersion{ found: 999, expected: SAVE_VERSION })));
+}
+
+#[test]
+fn corrupt_files_are_rejected() {
+	let save = game_in_progress(1).to_save_string();
+	assert!(matches!(Game::from_save_str(&save[..save.len() / 2]), Err(SaveError::Corrupt(_))));
+	assert!(matches!(Game::from_save_str(""), Err(SaveError::Corrupt(_))));
+	assert!(matches!(Game::from_save_str(&format!("{{\"version\":{}}}", SAVE_VERSION)), Err(SaveError::Corrupt(_))));
+	assert!(matches!(Game::load(&std::env::temp_dir().join("solitaire-core-test-missing.json")), Err(SaveError::Io(_))));
+
+	// parses fine, but a card has gone missing
+	let mut broken = game_in_progress(1);
+	broken.piles[0].visible.clear();
+	broken.piles[0].hidden.clear();
+	assert!(matches!(Game::from_save_str(&broken.to_save_string()), Err(SaveError::Corrupt(_))));
+}
+
+#[test]
+fn tampered_histories_are_rejected() {
+	let game = game_in_progress(2);
+	let card_move = game.history.iter().rposition(|entry| matches!(entry.mv, Move::CardMove{ .. })).unwrap();
+
+	// a move to a pile that doesn't exist would make undo panic
+	let mut broken = game.clone();
+	if let Move::CardMove{ dest, .. } = &mut broken.history[card_move].mv {
+		*dest = MoveDest::ToPile(9);
+	}
+	assert!(matches!(Game::from_save_str(&broken.to_save_string()), Err(SaveError::Corrupt(_))));
+
+	// a move missing from the middle of the history
+	let mut broken = game.clone();
+	broken.history.remove(game.history.len() / 2);
+	assert!(matches!(Game::from_save_str(&broken.to_save_string()), Err(SaveError::Corrupt(_))));
+
+	// a move waiting to be redone that can't be made
+	let mut broken = game.clone();
+	broken.redo_stack.push(Move::DrawStock{ n_cards: 9 });
+	assert!(matches!(Game::from_save_str(&broken.to_save_string()), Err(SaveError::Corrupt(_))));
+}
@@ -0,0 +1,152 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{play_random, NEARLY_WON};
+use solitaire_core::{time_text, DrawCount, Game, GameStatus, Move, MoveDest, MoveSrc, Rng, Rules, Scoring};
+
+fn rules(draw_count: DrawCount, scoring: Scoring) -> Rules {
+	return Rules{ draw_count, scoring, ..Rules::default() }
+}
+
+#[test]
+fn games_start_with_the_initial_score() {
+	assert_eq!(Game::from_seed(1, rules(DrawCount::One, Scoring::Standard)).score(), 0);
+	assert_eq!(Game::from_seed(1, rules(DrawCount::One, Scoring::Vegas)).score(), -52);
+}
+
+#[test]
+fn standard_points_for_each_kind_of_move() {
+	let r = rules(DrawCount::One, Scoring::Standard);
+	let scoring = Scoring::Standard;
+	let card = Game::from_seed(1, r).piles[0].visible[0];
+	let from_pile = |reveals_hidden_card| MoveSrc::FromPile{
+		pile_index: 0, n_cards: 1, progress_value: 0, reveals_hidden_card, target_card_index: 0,
+	};
+
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: MoveSrc::FromStock, dest: MoveDest::ToPile(1) }, 1), 5);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: MoveSrc::FromStock, dest: MoveDest::ToFoundation(card.suit) }, 1), 10);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: from_pile(false), dest: MoveDest::ToFoundation(card.suit) }, 1), 10);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: from_pile(true), dest: MoveDest::ToFoundation(card.suit) }, 1), 15);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: from_pile(false), dest: MoveDest::ToPile(1) }, 1), 0);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: from_pile(true), dest: MoveDest::ToPile(1) }, 1), 5);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: MoveSrc::FromFoundation(card.suit), dest: MoveDest::ToPile(1) }, 1), -15);
+	assert_eq!(scoring.move_points(&r, Move::DrawStock{ n_cards: 1 }, 1), 0);
+	assert_eq!(scoring.move_points(&r, Move::RecycleWaste, 2), -100);
+
+	let r3 = rules(DrawCount::Three, Scoring::Standard);
+	assert_eq!(scoring.move_points(&r3, Move::RecycleWaste, 3), 0);
+	assert_eq!(scoring.move_points(&r3, Move::RecycleWaste, 4), -20);
+}
+
+#[test]
+fn vegas_points_for_each_kind_of_move() {
+	let r = rules(DrawCount::One, Scoring::Vegas);
+	let scoring = Scoring::Vegas;
+	let card = Game::from_seed(1, r).piles[0].visible[0];
+
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: MoveSrc::FromStock, dest: MoveDest::ToFoundation(card.suit) }, 1), 5);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: MoveSrc::FromStock, dest: MoveDest::ToPile(1) }, 1), 0);
+	assert_eq!(scoring.move_points(&r, Move::CardMove{ card, src: MoveSrc::FromFoundation(card.suit), dest: MoveDest::ToPile(1) }, 1), -5);
+	assert_eq!(scoring.move_points(&r, Move::RecycleWaste, 2), 0);
+}
+
+#[test]
+fn undo_restores_the_score_and_redo_reapplies_it() {
+	for scoring in [Scoring::Standard, Scoring::Vegas] {
+		for seed in 0..20 {
+			let mut rng = Rng::new(seed);
+			let mut game = Game::from_seed(seed, rules(DrawCount::One, scoring));
+			let mut scores = vec![game.points];
+			play_random(&mut game, &mut rng, 150, |game, _| {
+				scores.push(game.points);
+				if scoring == Scoring::Standard {
+					assert!(game.points >= 0);
+				}
+			});
+
+			for score in scores.iter().rev().skip(1) {
+				game.undo_move();
+				assert_eq!(game.points, *score);
+			}
+			for score in scores.iter().skip(1) {
+				assert!(game.redo_move());
+				assert_eq!(game.points, *score);
+			}
+		}
+	}
+}
+
+#[test]
+fn standard_score_loses_points_over_time() {
+	let mut game = Game::from_seed(3, rules(DrawCount::One, Scoring::Standard));
+	game.points = 100;
+	game.elapsed = 35.;
+	assert_eq!(game.score(), 94);
+	game.elapsed = 1000.;
+	assert_eq!(game.score(), 0);
+}
+
+#[test]
+fn the_clock_costs_points_every_ten_seconds() {
+	let mut game = Game::from_seed(3, rules(DrawCount::One, Scoring::Standard));
+	game.tick(60.);
+	assert_eq!(game.elapsed, 0.);
+	game.exec_move(game.legal_moves()[0]);
+	game.points = 100;
+
+	game.tick(9.5);
+	assert_eq!(game.total_score(), 100);
+	game.tick(1.);
+	assert_eq!(game.total_score(), 98);
+	game.tick(9.);
+	assert_eq!(game.total_score(), 98);
+	game.tick(1.);
+	assert_eq!(game.total_score(), 96);
+}
+
+#[test]
+fn winning_after_thirty_seconds_earns_a_time_bonus() {
+	let win = |secs: f64| {
+		let mut game = Game::from_position(NEARLY_WON).unwrap();
+		assert!(game.exec_move(game.parse_move("1>F").unwrap()));
+		game.tick(secs);
+		for mv in ["2>F", "3>F", "4>F"] {
+			assert!(game.exec_move(game.parse_move(mv).unwrap()));
+		}
+		assert_eq!(game.status(), GameStatus::Won);
+		// the clock stops once the game is won
+		let score = game.total_score();
+		game.tick(100.);
+		assert_eq!(game.elapsed, secs);
+		assert_eq!(game.total_score(), score);
+		return score
+	};
+
+	// 40 points for the four kings, less the time penalty, plus 700,000 / seconds
+	assert_eq!(win(40.), 40 - 8 + 17_500);
+	assert_eq!(win(100.), 40 - 20 + 7_000);
+	assert_eq!(win(29.), 40 - 4);
+}
+
+#[test]
+fn vegas_balance_carries_over() {
+	let mut game = Game::from_seed(3, rules(DrawCount::One, Scoring::Vegas));
+	game.carried_score = 30;
+	assert_eq!(game.total_score(), -22);
+}
+
+#[test]
+fn scores_and_times_are_shown_the_same_everywhere() {
+	let mut game = Game::from_seed(3, rules(DrawCount::One, Scoring::Standard));
+	game.points = 120;
+	assert_eq!(game.score_text(), "Score: 120");
+
+	let mut game = Game::from_seed(3, rules(DrawCount::One, Scoring::Vegas));
+	assert_eq!(game.score_text(), "Vegas: -$52");
+	game.carried_score = 60;
+	assert_eq!(game.score_text(), "Vegas: $8");
+
+	assert_eq!(time_text(0.), "0:00");
+	assert_eq!(time_text(125.9), "2:05");
+}
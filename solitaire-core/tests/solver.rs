@@ -0,0 +1,34 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{STUCK, TANGLED};
+use solitaire_core::{solve, Game, GameStatus, Rules, SolveResult, SolverConfig};
+
+#[test]
+fn solutions_win_the_game() {
+	let mut game = Game::from_position(TANGLED).unwrap();
+	let moves = match solve(&game, SolverConfig::default()).result {
+		SolveResult::Solved(moves) => moves,
+		other => panic!("expected a solution, got {:?}", other),
+	};
+	for mv in moves {
+		assert!(game.exec_move(mv), "{}", mv);
+	}
+	assert_eq!(game.status(), GameStatus::Won);
+}
+
+#[test]
+fn dead_positions_are_unsolvable() {
+	let game = Game::from_position(STUCK).unwrap();
+	assert!(matches!(solve(&game, SolverConfig::default()).result, SolveResult::Unsolvable));
+}
+
+#[test]
+fn the_solver_gives_up_when_it_runs_out_of_nodes() {
+	let game = Game::from_seed(1, Rules::default());
+	let config = SolverConfig { max_nodes: 2, time_limit: None };
+	let solution = solve(&game, config);
+	assert!(matches!(solution.result, SolveResult::GaveUp));
+	assert_eq!(solution.nodes, 2);
+}
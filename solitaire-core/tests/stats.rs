@@ -0,0 +1,95 @@
+#![allow(clippy::needless_return)]
+
+use solitaire_core::{DrawCount, Game, Move, MouseTarget, Rank, Rules, SaveError, Scoring, Stats, Suit, STATS_VERSION};
+
+// a game that's been started, with the first card turned over from the stock
+fn started_game(seed: u64, rules: Rules) -> Game {
+	let mut game = Game::from_seed(seed, rules);
+	let mv: Move = game.calc_moves(MouseTarget::StockDeck).unwrap()[0];
+	assert!(game.exec_move(mv));
+	return game
+}
+
+// a game that has been won, by moving every card straight onto the foundations
+fn won_game(rules: Rules, moves: u32, elapsed: f64) -> Game {
+	let mut game = started_game(1, rules);
+	game.stock.clear();
+	game.waste.clear();
+	for pile in game.piles.iter_mut() {
+		pile.hidden.clear();
+		pile.visible.clear();
+	}
+	for suit in Suit::all() {
+		game.foundation_fill_levels.insert(*suit, Rank::King);
+	}
+	game.moves = moves;
+	game.elapsed = elapsed;
+	return game
+}
+
+#[test]
+fn unstarted_games_are_not_recorded() {
+	let mut stats = Stats::default();
+	let mut game = Game::from_seed(1, Rules::default());
+	assert!(!stats.record(&mut game));
+	assert_eq!(stats.variant(DrawCount::One, Scoring::Standard).played, 0);
+}
+
+#[test]
+fn games_are_only_recorded_once() {
+	let mut stats = Stats::default();
+	let mut game = started_game(1, Rules::default());
+	assert!(stats.record(&mut game));
+	assert!(!stats.record(&mut game));
+	assert_eq!(stats.variant(DrawCount::One, Scoring::Standard).played, 1);
+}
+
+#[test]
+fn streaks_and_records() {
+	let rules = Rules::default();
+	let mut stats = Stats::default();
+	for (moves, elapsed) in [(120, 300.), (100, 400.), (150, 200.)] {
+		stats.record(&mut won_game(rules, moves, elapsed));
+	}
+	stats.record(&mut started_game(2, rules));
+	stats.record(&mut won_game(rules, 130, 250.));
+
+	let v = stats.variant(DrawCount::One, Scoring::Standard);
+	assert_eq!(v.played, 5);
+	assert_eq!(v.won, 4);
+	assert_eq!(v.current_streak, 1);
+	assert_eq!(v.best_streak, 3);
+	assert_eq!(v.fastest_win, Some(200.));
+	assert_eq!(v.fewest_moves, Some(100));
+	assert!(v.average_score().unwrap() > 0.);
+}
+
+#[test]
+fn variants_are_kept_separately() {
+	let mut stats = Stats::default();
+	let draw_3 = Rules{ draw_count: DrawCount::Three, ..Rules::default() };
+	let vegas = Rules{ scoring: Scoring::Vegas, ..Rules::default() };
+	stats.record(&mut won_game(draw_3, 100, 100.));
+	stats.record(&mut started_game(1, vegas));
+
+	assert_eq!(stats.variant(DrawCount::Three, Scoring::Standard).won, 1);
+	assert_eq!(stats.variant(DrawCount::One, Scoring::Vegas).played, 1);
+	assert_eq!(stats.variant(DrawCount::One, Scoring::Vegas).average_score(), Some(-52.));
+	assert_eq!(stats.variant(DrawCount::One, Scoring::Standard).played, 0);
+
+	stats.reset();
+	assert_eq!(stats, Stats::default());
+}
+
+#[test]
+fn stats_round_trip_through_json() {
+	let mut stats = Stats::default();
+	stats.record(&mut won_game(Rules::default(), 100, 123.5));
+	stats.record(&mut started_game(3, Rules::default()));
+	assert_eq!(Stats::from_json(&stats.to_json()).unwrap(), stats);
+
+	let wrong_version = stats.to_json().replacen(&format!("\"version\":{}", STATS_VERSION), "\"version\":999", 1);
+	assert!(matches!(Stats::from_json(&wrong_version), Err(SaveError::IncompatibleVersion{ found: 999, .. })));
+	assert!(matches!(Stats::from_json("{\"version\":1"), Err(SaveError::Corrupt(_))));
+	assert!(matches!(Stats::from_json(&format!("{{\"version\":{}}}", STATS_VERSION)), Err(SaveError::Corrupt(_))));
+}
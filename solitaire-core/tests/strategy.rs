@@ -0,0 +1,124 @@
+#![allow(clippy::needless_return)]
+
+use solitaire_core::{Game, GameStatus, Greedy, Move, RandomMoves, Rules, Strategy};
+
+// plays until the game's over, or the strategy has made the given number of moves
+fn play(game: &mut Game, strategy: &mut dyn Strategy, max_moves: usize) {
+	for _ in 0..max_moves {
+		if game.status() != GameStatus::InProgress || !game.play_strategy(strategy) {
+			break
+		}
+	}
+}
+
+// always picks the last move it's offered
+struct LastMove;
+
+impl Strategy for LastMove {
+	fn choose(&mut self, _game: &Game, moves: &[Move]) -> Move {
+		return *moves.last().unwrap()
+	}
+}
+
+// picks a move that was legal in the position before last, to check that illegal choices are refused
+struct Stale {
+	previous: Option<Move>,
+}
+
+impl Strategy for Stale {
+	fn choose(&mut self, _game: &Game, moves: &[Move]) -> Move {
+		let mv = self.previous.unwrap_or(moves[0]);
+		self.previous = Some(moves[0]);
+		return mv
+	}
+}
+
+// a move of each kind: progress moves (9S uncovers a hidden card, TH empties its pile), a card to
+// its foundation, the waste onto a pile, drawing from the stock, a run between piles that doesn't
+// get anywhere, and a card back off its foundation
+const EVERY_KIND_OF_MOVE: &str = "
+	draw: 1
+	pass limit: unlimited
+	scoring: standard
+	pass: 1
+	stock: 3D 4D 5D 6D 7D 8D 9D TD JD AC 2C 4C 6C 8C 9C TC QC AH 2H 3H 5H 6H 7H 9H JH QH KH 4S 6S 7S 8S TS JS
+	waste: 8H
+	foundations: 2D - - 2S
+	1: KS | 9S
+	2: TH
+	3: QD | JC
+	4: KD | 5C 4H 3S
+	5: 3C
+	6: QS | 5S
+	7: KC | 7C
+";
+
+#[test]
+fn greedy_ranks_every_kind_of_move() {
+	let game = Game::from_position(EVERY_KIND_OF_MOVE).unwrap();
+	let mut moves = game.legal_moves();
+	let mut order = Vec::new();
+	while !moves.is_empty() {
+		let mv = Greedy.choose(&game, &moves);
+		order.push(mv.to_string());
+		moves.retain(|m| *m != mv);
+	}
+	assert_eq!(order, ["1>2", "2>3", "4>F", "W>1", "D", "4:2>6", "FD>4", "FD>5"]);
+}
+
+#[test]
+fn greedy_doesnt_depend_on_the_order_of_different_moves() {
+	let game = Game::from_position(EVERY_KIND_OF_MOVE).unwrap();
+	let moves = game.legal_moves();
+	let mut reversed = moves.clone();
+	reversed.reverse();
+	assert_eq!(Greedy.choose(&game, &moves).to_string(), "1>2");
+	assert_eq!(Greedy.choose(&game, &reversed).to_string(), "1>2");
+}
+
+#[test]
+fn auto_move_plays_greedily() {
+	let mut game = Game::from_position(EVERY_KIND_OF_MOVE).unwrap();
+	for expected in ["1>2", "4>F", "5>4"] {
+		let before = game.clone();
+		assert!(game.auto_move());
+		assert_eq!(game.history.last().unwrap().mv.to_string(), expected, "{}", before);
+	}
+}
+
+#[test]
+fn custom_strategies_can_play() {
+	let mut game = Game::from_seed(3, Rules::default());
+	play(&mut game, &mut LastMove, 50);
+	assert!(game.moves > 0);
+	assert_eq!(game.validate(), Ok(()));
+}
+
+#[test]
+fn random_moves_are_reproducible() {
+	let mut a = Game::from_seed(8, Rules::default());
+	let mut b = a.clone();
+	play(&mut a, &mut RandomMoves::new(1), 100);
+	play(&mut b, &mut RandomMoves::new(1), 100);
+	assert_eq!(a.history, b.history);
+
+	let mut c = Game::from_seed(8, Rules::default());
+	play(&mut c, &mut RandomMoves::new(2), 100);
+	assert_ne!(a.history, c.history);
+}
+
+#[test]
+fn illegal_choices_are_refused() {
+	let mut game = Game::from_seed(5, Rules::default());
+	let mut strategy = Stale { previous: None };
+	let mut refused = false;
+	for _ in 0..100 {
+		let before = game.clone();
+		if !game.play_strategy(&mut strategy) {
+			assert_eq!(game, before);
+			refused = true;
+			break
+		}
+	}
+	assert!(refused);
+}
@@ -0,0 +1,137 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{all_rules, play_random, NEARLY_WON};
+use solitaire_core::{Game, Rng, Rules};
+
+// plays `n_moves` random moves, returning a copy of the game from before each move and after the last one
+fn play_random_moves(game: &mut Game, rng: &mut Rng, n_moves: usize) -> Vec<Game> {
+	let mut states = vec![game.clone()];
+	play_random(game, rng, n_moves, |game, _| states.push(game.clone()));
+	return states
+}
+
+// the parts of the game that undo and redo have to restore
+fn assert_same_position(a: &Game, b: &Game) {
+	assert_eq!(a.stock, b.stock);
+	assert_eq!(a.waste, b.waste);
+	assert_eq!(a.piles, b.piles);
+	assert_eq!(a.foundation_fill_levels, b.foundation_fill_levels);
+	assert_eq!(a.passes, b.passes);
+	assert_eq!(a.history, b.history);
+	assert_eq!(a.points, b.points);
+}
+
+#[test]
+fn undo_restores_every_previous_position() {
+	for rules in all_rules() {
+		for seed in 0..20 {
+			let mut game = Game::from_seed(seed, rules);
+			let states = play_random_moves(&mut game, &mut Rng::new(seed), 200);
+
+			for state in states.iter().rev().skip(1) {
+				game.undo_move();
+				assert_same_position(&game, state);
+			}
+		}
+	}
+}
+
+#[test]
+fn redo_after_undo_round_trips_exactly() {
+	for rules in all_rules() {
+		for seed in 0..20 {
+			let mut rng = Rng::new(seed);
+			let mut game = Game::from_seed(seed, rules);
+			let states = play_random_moves(&mut game, &mut rng, 150);
+			let end = game.clone();
+
+			let n_undos = rng.gen_below(states.len() as u64) as usize;
+			for _ in 0..n_undos {
+				game.undo_move();
+			}
+			assert_eq!(game.redo_stack.len(), n_undos);
+			assert_same_position(&game, &states[states.len() - 1 - n_undos]);
+
+			for _ in 0..n_undos {
+				assert!(game.redo_move());
+			}
+			assert!(!game.redo_move());
+			assert_same_position(&game, &end);
+			assert_eq!(game.history, end.history);
+
+			// undoing and redoing still count towards the totals
+			assert_eq!(game.moves, end.moves + n_undos as u32);
+			assert_eq!(game.undos, end.undos + n_undos as u32);
+		}
+	}
+}
+
+#[test]
+fn interleaved_undo_and_redo_round_trips() {
+	for seed in 0..20 {
+		let mut rng = Rng::new(seed);
+		let mut game = Game::from_seed(seed, Rules::default());
+		let states = play_random_moves(&mut game, &mut rng, 100);
+
+		// wander back and forth through the history, checking the position at every step
+		let mut position = states.len() - 1;
+		for _ in 0..300 {
+			if rng.gen_below(2) == 0 && position > 0 {
+				game.undo_move();
+				position -= 1;
+			} else if game.redo_move() {
+				position += 1;
+			}
+			assert_same_position(&game, &states[position]);
+		}
+	}
+}
+
+#[test]
+fn new_move_clears_the_redo_stack() {
+	let mut game = Game::from_seed(1, Rules::default());
+	let mut rng = Rng::new(1);
+	play_random_moves(&mut game, &mut rng, 20);
+
+	game.undo_move();
+	game.undo_move();
+	assert_eq!(game.redo_stack.len(), 2);
+
+	let mv = game.legal_moves()[0];
+	assert!(game.exec_move(mv));
+	assert!(game.redo_stack.is_empty());
+	assert!(!game.redo_move());
+}
+
+#[test]
+fn undo_with_no_history_does_nothing() {
+	let mut game = Game::from_seed(7, Rules::default());
+	let before = game.clone();
+	game.undo_move();
+	assert_eq!(game, before);
+	assert!(!game.redo_move());
+}
+
+#[test]
+fn restarting_goes_back_to_the_deal() {
+	for seed in 0..5 {
+		let dealt = Game::from_seed(seed, Rules::default());
+		let mut game = dealt.clone();
+		play_random_moves(&mut game, &mut Rng::new(seed), 50);
+		game.undo_move();
+		assert_eq!(game.restarted(), dealt);
+	}
+}
+
+#[test]
+fn restarting_a_loaded_position_goes_back_to_the_position() {
+	let loaded = Game::from_position(NEARLY_WON).unwrap();
+	let mut game = loaded.clone();
+	for text in ["1>F", "2>5"] {
+		let mv = game.parse_move(text).unwrap();
+		assert!(game.exec_move(mv), "{}", text);
+	}
+	assert_eq!(game.restarted(), loaded);
+}
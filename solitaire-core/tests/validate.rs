@@ -0,0 +1,109 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{all_rules, play_random};
+use solitaire_core::{Game, HistoryEntry, Move, MouseTarget, MoveDest, MoveSrc, PassLimit, Rank, Rng, Rules, ValidationError};
+
+#[test]
+fn undo_chains_stay_valid() {
+	for rules in all_rules() {
+		for seed in 0..30 {
+			let mut rng = Rng::new(seed);
+			let mut game = Game::from_seed(seed, rules);
+			play_random(&mut game, &mut rng, 300, |game, rng| {
+				assert_eq!(game.validate(), Ok(()), "seed {}\n{}", seed, game);
+				// now and then, undo a run of moves and sometimes redo some of them again
+				if rng.gen_below(8) == 0 {
+					let n_undos = rng.gen_below(6);
+					for _ in 0..n_undos {
+						game.undo_move();
+						assert_eq!(game.validate(), Ok(()), "seed {}\n{}", seed, game);
+					}
+					for _ in 0..rng.gen_below(n_undos + 1) {
+						game.redo_move();
+						assert_eq!(game.validate(), Ok(()), "seed {}\n{}", seed, game);
+					}
+				}
+			});
+			assert_eq!(game.validate_history(), Ok(()), "seed {}\n{}", seed, game);
+			while !game.history.is_empty() {
+				game.undo_move();
+				assert_eq!(game.validate(), Ok(()), "seed {}\n{}", seed, game);
+			}
+		}
+	}
+}
+
+#[test]
+fn broken_games_are_caught() {
+	let game = Game::from_seed(4, Rules::default());
+
+	let mut broken = game.clone();
+	broken.piles.pop();
+	assert_eq!(broken.validate(), Err(ValidationError::WrongNumberOfPiles(6)));
+
+	let mut broken = game.clone();
+	let card = broken.stock.pop().unwrap();
+	assert_eq!(broken.validate(), Err(ValidationError::MissingCard(card)));
+	broken.waste.push(card);
+	broken.waste.push(card);
+	assert_eq!(broken.validate(), Err(ValidationError::DuplicateCard(card)));
+
+	let mut broken = game.clone();
+	let card = broken.stock[0];
+	broken.foundation_fill_levels.insert(card.suit, Rank::King);
+	assert!(matches!(broken.validate(), Err(ValidationError::AlreadyOnFoundation(_))));
+
+	let mut broken = game.clone();
+	let card = broken.piles[1].visible.pop().unwrap();
+	broken.piles[1].hidden.push(card);
+	assert_eq!(broken.validate(), Err(ValidationError::UncoveredHiddenCards{ pile_index: 1 }));
+
+	let mut broken = game.clone();
+	// a card of the same colour on top of pile 1's card
+	let top = broken.piles[0].visible[0];
+	let card = broken.stock.remove(broken.stock.iter().position(|card| card.colour() == top.colour()).unwrap());
+	broken.piles[0].visible.push(card);
+	assert_eq!(broken.validate(), Err(ValidationError::IllegalRun{ pile_index: 0, card, onto: top }));
+
+	let mut broken = Game::from_seed(4, Rules { pass_limit: PassLimit::Passes(1), ..Rules::default() });
+	broken.passes = 2;
+	assert_eq!(broken.validate(), Err(ValidationError::TooManyPasses{ passes: 2, limit: 1 }));
+}
+
+#[test]
+fn tampered_histories_are_caught() {
+	let mut game = Game::from_seed(4, Rules::default());
+	while game.auto_move() {}
+	game.undo_move();
+	assert_eq!(game.validate_history(), Ok(()));
+	let pile_move = game.history.iter().rposition(|entry| matches!(entry.mv, Move::CardMove{ src: MoveSrc::FromPile{..}, dest: MoveDest::ToPile(_), .. })).unwrap();
+
+	// changes the last move from one pile to another
+	let tamper = |change: &dyn Fn(&mut HistoryEntry)| {
+		let mut broken = game.clone();
+		change(&mut broken.history[pile_move]);
+		return broken.validate_history()
+	};
+	let impossible = Err(ValidationError::ImpossibleMove{ move_index: pile_move });
+	assert_eq!(tamper(&|entry| if let Move::CardMove{ dest, .. } = &mut entry.mv { *dest = MoveDest::ToPile(9) }), impossible);
+	assert_eq!(tamper(&|entry| if let Move::CardMove{ src: MoveSrc::FromPile{ pile_index, .. }, .. } = &mut entry.mv { *pile_index = 9 }), impossible);
+	assert_eq!(tamper(&|entry| if let Move::CardMove{ src: MoveSrc::FromPile{ n_cards, .. }, .. } = &mut entry.mv { *n_cards = 50 }), impossible);
+	assert_eq!(tamper(&|entry| entry.points += 10), impossible);
+
+	let mut broken = game.clone();
+	broken.redo_stack.push(Move::DrawStock{ n_cards: 9 });
+	assert_eq!(broken.validate_history(), Err(ValidationError::ImpossibleRedo{ redo_index: 1 }));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "the game is invalid")]
+fn moves_on_a_broken_board_panic_in_debug_builds() {
+	let mut game = Game::from_seed(4, Rules::default());
+	let card = game.stock[0];
+	game.piles[6].hidden.push(card);
+	let mv = game.calc_moves(MouseTarget::StockDeck).unwrap()[0];
+	game.exec_move(mv);
+}
@@ -0,0 +1,215 @@
+#![allow(clippy::needless_return)]
+
+// Plays a run of deals to the end with one strategy and reports how well it did, so that changes to
+// a strategy can be measured rather than guessed at.
+//
+// A line of CSV for each deal goes to stdout, and the summary goes to stderr, eg.
+//
+//     cargo run --release -p solitaire-sim -- --games 1000 --strategy greedy > results.csv
+
+use solitaire_core::{solve, DrawCount, Game, GameStatus, Greedy, PassLimit, RandomMoves, Rules, SolveResult, SolverConfig, Strategy};
+use std::time::Instant;
+
+const USAGE: &str = "usage: solitaire-sim [--games N] [--first-seed N] [--strategy greedy|solver|random] [--draw 1|3] [--passes unlimited|N] [--max-moves N] [--solver-nodes N]";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum StrategyKind {
+	Greedy, // the same as Game::auto_move, as used by the A key
+	Solver, // the exhaustive solver, which plays a winning line if it can find one
+	Random, // any legal move at all
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Outcome {
+	Won,
+	Stuck, // there were no moves left to make
+	Looped, // greedy play kept coming back to the same position (see Game::is_looping)
+	MoveLimit, // the game went on for too many moves
+	Unwinnable, // the solver proved that the deal can't be won
+	GaveUp, // the solver ran out of nodes before finishing its search
+}
+
+impl Outcome {
+	fn name(&self) -> &str {
+		return match self {
+			Outcome::Won => "won",
+			Outcome::Stuck => "stuck",
+			Outcome::Looped => "looped",
+			Outcome::MoveLimit => "move_limit",
+			Outcome::Unwinnable => "unwinnable",
+			Outcome::GaveUp => "gave_up",
+		}
+	}
+}
+
+struct Options {
+	games: u64,
+	first_seed: u64,
+	strategy: StrategyKind,
+	rules: Rules,
+	max_moves: u32,
+	solver_nodes: usize,
+}
+
+struct GameResult {
+	seed: u64,
+	outcome: Outcome,
+	moves: u32,
+	cards_to_foundation: usize,
+	millis: u128,
+}
+
+fn main() {
+	let options = match parse_options() {
+		Ok(options) => options,
+		Err(err) => {
+			eprintln!("{}\n{}", err, USAGE);
+			std::process::exit(2);
+		}
+	};
+
+	println!("seed,result,moves,cards_to_foundation,millis");
+	let started_at = Instant::now();
+	let mut results = Vec::new();
+	for seed in options.first_seed..options.first_seed + options.games {
+		let result = play(seed, &options);
+		println!("{},{},{},{},{}", result.seed, result.outcome.name(), result.moves, result.cards_to_foundation, result.millis);
+		results.push(result);
+	}
+	print_summary(&options, &results, started_at.elapsed().as_secs_f64());
+}
+
+fn parse_options() -> Result<Options, String> {
+	let parse_number = |flag: &str, default: u64| -> Result<u64, String> {
+		return match arg_value(flag) {
+			Some(value) => value.parse().map_err(|_| format!("{} must be a number, not {}", flag, value)),
+			None => Ok(default),
+		}
+	};
+
+	let strategy = match arg_value("--strategy").as_deref() {
+		None | Some("greedy") => StrategyKind::Greedy,
+		Some("solver") => StrategyKind::Solver,
+		Some("random") => StrategyKind::Random,
+		Some(other) => return Err(format!("unknown strategy {}", other)),
+	};
+	let draw_count = match arg_value("--draw").as_deref() {
+		None | Some("1") => DrawCount::One,
+		Some("3") => DrawCount::Three,
+		Some(other) => return Err(format!("--draw must be 1 or 3, not {}", other)),
+	};
+	let pass_limit = match arg_value("--passes").as_deref() {
+		None | Some("unlimited") => PassLimit::Unlimited,
+		Some(passes) => match passes.parse::<u32>() {
+			Ok(passes) if passes > 0 => PassLimit::Passes(passes),
+			_ => return Err(format!("--passes must be \"unlimited\" or a number, not {}", passes)),
+		},
+	};
+
+	let games = parse_number("--games", 100)?;
+	let first_seed = parse_number("--first-seed", 0)?;
+	if first_seed.checked_add(games).is_none() {
+		return Err(format!("--first-seed {} is too big to play {} games from", first_seed, games))
+	}
+
+	return Ok(Options {
+		games,
+		first_seed,
+		strategy,
+		rules: Rules { draw_count, pass_limit, ..Rules::default() },
+		max_moves: parse_number("--max-moves", 1000)? as u32,
+		solver_nodes: parse_number("--solver-nodes", 100_000)? as usize,
+	})
+}
+
+// returns the value following the given flag on the command line, eg. `--games 100`
+fn arg_value(flag: &str) -> Option<String> {
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		if arg == flag {
+			return args.next()
+		}
+	}
+	return None
+}
+
+// plays the deal with the given seed to the end
+fn play(seed: u64, options: &Options) -> GameResult {
+	let started_at = Instant::now();
+	let mut game = Game::from_seed(seed, options.rules);
+	let outcome = match options.strategy {
+		StrategyKind::Solver => play_solution(&mut game, options),
+		StrategyKind::Greedy | StrategyKind::Random => play_moves(&mut game, seed, options),
+	};
+	return GameResult {
+		seed,
+		outcome,
+		moves: game.moves,
+		cards_to_foundation: cards_to_foundation(&game),
+		millis: started_at.elapsed().as_millis(),
+	}
+}
+
+fn play_solution(game: &mut Game, options: &Options) -> Outcome {
+	let config = SolverConfig { max_nodes: options.solver_nodes, time_limit: None };
+	return match solve(game, config).result {
+		SolveResult::Solved(moves) => {
+			for mv in moves {
+				game.exec_move(mv);
+			}
+			Outcome::Won
+		}
+		SolveResult::Unsolvable => Outcome::Unwinnable,
+		SolveResult::GaveUp => Outcome::GaveUp,
+	}
+}
+
+// makes one move at a time until the game's over, or it stops getting anywhere
+fn play_moves(game: &mut Game, seed: u64, options: &Options) -> Outcome {
+	let mut strategy: Box<dyn Strategy> = match options.strategy {
+		StrategyKind::Random => Box::new(RandomMoves::new(seed)),
+		_ => Box::new(Greedy),
+	};
+	loop {
+		match game.status() {
+			GameStatus::Won => return Outcome::Won,
+			GameStatus::NoMovesRemaining => return Outcome::Stuck,
+			GameStatus::InProgress => {}
+		}
+		if game.moves >= options.max_moves {
+			return Outcome::MoveLimit
+		}
+
+		if !game.play_strategy(strategy.as_mut()) {
+			return Outcome::Stuck
+		}
+		// greedy always makes the same move in the same position, so once a position comes back it'll
+		// go round forever. Random moves wander off again, so they carry on until the move limit
+		if options.strategy == StrategyKind::Greedy && game.is_looping() {
+			return Outcome::Looped
+		}
+	}
+}
+
+fn cards_to_foundation(game: &Game) -> usize {
+	return game.foundation_fill_levels.values().map(|rank| rank.index() as usize + 1).sum()
+}
+
+fn print_summary(options: &Options, results: &[GameResult], secs: f64) {
+	let n = results.len().max(1) as f64;
+	let count = |outcome: Outcome| results.iter().filter(|result| result.outcome == outcome).count();
+	let won = count(Outcome::Won);
+
+	eprintln!("{:?} strategy, Draw-{}, {} games from seed {}, in {:.1}s", options.strategy, options.rules.draw_count.n_cards(), results.len(), options.first_seed, secs);
+	eprintln!("won: {} ({:.1}%)", won, won as f64 * 100. / n);
+	let others = [Outcome::Stuck, Outcome::Looped, Outcome::MoveLimit, Outcome::Unwinnable, Outcome::GaveUp];
+	let others: Vec<String> = others.iter()
+		.filter(|outcome| count(**outcome) > 0)
+		.map(|outcome| format!("{}: {}", outcome.name(), count(*outcome)))
+		.collect();
+	if !others.is_empty() {
+		eprintln!("{}", others.join(", "));
+	}
+	eprintln!("average moves: {:.1}", results.iter().map(|result| result.moves as f64).sum::<f64>() / n);
+	eprintln!("average cards to foundation: {:.1}", results.iter().map(|result| result.cards_to_foundation as f64).sum::<f64>() / n);
+}
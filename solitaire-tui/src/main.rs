@@ -0,0 +1,325 @@
+#![allow(clippy::needless_return)]
+
+// A terminal frontend for solitaire-core, for playing (or debugging) where no window can open, eg.
+// over SSH.
+//
+// A cursor picks out a place on the board the way the mouse does in the windowed game: selecting a
+// card makes its move if there's only one place it can go, and otherwise waits for the destination
+// to be selected.
+
+mod render;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute};
+use solitaire_core::{hints, DrawCount, Game, GameStatus, MouseTarget, Move, MoveDest, MoveInProgress, Rules, Suit, N_PILES};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const TICK: Duration = Duration::from_millis(250); // how often the clock is redrawn when no keys are pressed
+
+// a place on the board that the cursor can be on
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Slot {
+	Stock,
+	Waste,
+	Foundation(usize), // by foundation index
+	Pile(usize),
+}
+
+pub struct App {
+	pub game: Game,
+	pub cursor: Slot,
+	pub depth: usize, // the number of cards selected in the pile under the cursor (1 = just the top card)
+	pub message: Option<String>,
+	quit: bool,
+}
+
+// puts the terminal into raw mode on an alternate screen, and back again when dropped
+struct Terminal;
+
+impl Terminal {
+	fn enter() -> io::Result<Terminal> {
+		terminal::enable_raw_mode()?;
+		execute!(io::stdout(), EnterAlternateScreen, cursor::Hide)?;
+		return Ok(Terminal)
+	}
+}
+
+impl Drop for Terminal {
+	fn drop(&mut self) {
+		let _ = execute!(io::stdout(), cursor::Show, LeaveAlternateScreen);
+		let _ = terminal::disable_raw_mode();
+	}
+}
+
+fn main() {
+	let game = match starting_game() {
+		Ok(game) => game,
+		Err(err) => {
+			eprintln!("{}", err);
+			std::process::exit(1);
+		}
+	};
+	let mut app = App { game, cursor: Slot::Pile(0), depth: 1, message: None, quit: false };
+
+	if let Err(err) = run(&mut app) {
+		eprintln!("Terminal error: {}", err);
+		std::process::exit(1);
+	}
+}
+
+// the game given on the command line (`--position <file>`, or `--seed <n>` and `--draw <1|3>`), or
+// a random deal
+fn starting_game() -> Result<Game, String> {
+	if let Some(path) = arg_value("--position") {
+		return Game::load_position(Path::new(&path)).map_err(|err| format!("Couldn't load the position {}: {}", path, err))
+	}
+	let draw_count = match arg_value("--draw").as_deref() {
+		None | Some("1") => DrawCount::One,
+		Some("3") => DrawCount::Three,
+		Some(other) => return Err(format!("--draw must be 1 or 3, not {}", other)),
+	};
+	let rules = Rules { draw_count, ..Rules::default() };
+	return match arg_value("--seed") {
+		Some(seed) => match seed.parse::<u64>() {
+			Ok(seed) => Ok(Game::from_seed(seed, rules)),
+			Err(_) => Err(format!("{} isn't a deal number", seed)),
+		},
+		None => Ok(new_game(rules)),
+	}
+}
+
+// returns the value following the given flag on the command line, eg. `--seed 1234`
+fn arg_value(flag: &str) -> Option<String> {
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		if arg == flag {
+			return args.next()
+		}
+	}
+	return None
+}
+
+// deals a game with a deal number taken from the clock
+fn new_game(rules: Rules) -> Game {
+	let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos();
+	return Game::from_seed(nanos as u64, rules)
+}
+
+fn run(app: &mut App) -> io::Result<()> {
+	let _terminal = Terminal::enter()?;
+	let mut out = io::stdout();
+	let mut last_tick = Instant::now();
+
+	while !app.quit {
+		render::draw(&mut out, app)?;
+		out.flush()?;
+
+		if event::poll(TICK)? {
+			if let Event::Key(key) = event::read()? {
+				if key.kind == KeyEventKind::Press {
+					handle_key(app, key);
+				}
+			}
+		}
+
+		let now = Instant::now();
+		app.game.tick((now - last_tick).as_secs_f64());
+		last_tick = now;
+	}
+	return Ok(())
+}
+
+fn handle_key(app: &mut App, key: KeyEvent) {
+	app.message = None;
+	let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+	match key.code {
+		KeyCode::Char('c') if ctrl => app.quit = true,
+		KeyCode::Char('y') if ctrl => redo(app),
+		KeyCode::Char('q') => app.quit = true,
+		KeyCode::Esc => app.game.move_in_progress = None,
+
+		KeyCode::Left => move_cursor(app, -1),
+		KeyCode::Right | KeyCode::Tab => move_cursor(app, 1),
+		KeyCode::Up => cursor_up(app),
+		KeyCode::Down => cursor_down(app),
+		KeyCode::Enter | KeyCode::Char(' ') => select_slot(app, app.cursor),
+
+		KeyCode::Char(c @ '1'..='7') => {
+			let pile_index = c as usize - '1' as usize;
+			app.cursor = Slot::Pile(pile_index);
+			app.depth = 1;
+			select_slot(app, app.cursor);
+		}
+		KeyCode::Char('s') => select_slot(app, Slot::Stock),
+		KeyCode::Char('w') => select_slot(app, Slot::Waste),
+		KeyCode::Char('f') => to_foundation(app),
+
+		KeyCode::Char('u') => {
+			app.game.move_in_progress = None;
+			app.game.undo_move();
+		}
+		KeyCode::Char('U') => redo(app),
+		KeyCode::Char('a') => {
+			app.game.move_in_progress = None;
+			if !app.game.auto_move() && app.game.is_looping() {
+				app.message = Some("No progress possible: auto-move is going round in circles. Press n for a new deal.".to_string());
+			}
+		}
+		KeyCode::Char('?') => {
+			app.message = Some(match hints(&app.game).first() {
+				Some(mv) => format!("Hint: {}", mv),
+				None => "No hints: there's nothing useful left to do".to_string(),
+			});
+		}
+		KeyCode::Char('n') => app.game = new_game(app.game.rules),
+		KeyCode::Char('t') => {
+			let draw_count = match app.game.rules.draw_count {
+				DrawCount::One => DrawCount::Three,
+				DrawCount::Three => DrawCount::One,
+			};
+			app.game = new_game(Rules { draw_count, ..app.game.rules });
+		}
+		_ => {}
+	}
+
+	app.depth = app.depth.clamp(1, max_depth(app).max(1));
+	match app.game.status() {
+		GameStatus::Won => app.message = Some("You won! Press n for a new deal.".to_string()),
+		GameStatus::NoMovesRemaining => app.message = Some("No moves left. Press u to undo, or n for a new deal.".to_string()),
+		GameStatus::InProgress => {}
+	}
+}
+
+fn redo(app: &mut App) {
+	app.game.move_in_progress = None;
+	app.game.redo_move();
+}
+
+// the most cards that can be selected in the pile under the cursor
+fn max_depth(app: &App) -> usize {
+	return match app.cursor {
+		Slot::Pile(pile_index) => app.game.piles[pile_index].visible.len(),
+		_ => 1,
+	}
+}
+
+// moves left or right along the row the cursor is in
+fn move_cursor(app: &mut App, step: isize) {
+	let wrap = |i: usize, n: usize| (i as isize + step).rem_euclid(n as isize) as usize;
+	let top_row = [Slot::Stock, Slot::Waste, Slot::Foundation(0), Slot::Foundation(1), Slot::Foundation(2), Slot::Foundation(3)];
+	app.cursor = match app.cursor {
+		Slot::Pile(pile_index) => Slot::Pile(wrap(pile_index, N_PILES as usize)),
+		slot => top_row[wrap(top_row.iter().position(|s| *s == slot).unwrap(), top_row.len())],
+	};
+	app.depth = 1;
+}
+
+// selects more of the pile under the cursor, or moves up to the stock and foundations
+fn cursor_up(app: &mut App) {
+	if let Slot::Pile(pile_index) = app.cursor {
+		if app.depth < max_depth(app) {
+			app.depth += 1;
+		} else {
+			app.cursor = render::slot_above(pile_index);
+			app.depth = 1;
+		}
+	}
+}
+
+// selects less of the pile under the cursor, or moves down to the piles
+fn cursor_down(app: &mut App) {
+	match app.cursor {
+		Slot::Pile(_) => app.depth = app.depth.saturating_sub(1).max(1),
+		slot => {
+			app.cursor = Slot::Pile(render::column(slot));
+			app.depth = 1;
+		}
+	}
+}
+
+// the target the mouse would be over to pick out this slot
+fn target(app: &App, slot: Slot) -> MouseTarget {
+	let game = &app.game;
+	return match slot {
+		Slot::Stock if game.stock.is_empty() => MouseTarget::Recycle,
+		Slot::Stock => MouseTarget::StockDeck,
+		Slot::Waste => MouseTarget::StockTop,
+		Slot::Foundation(i) => MouseTarget::Foundation(foundation_suit(i)),
+		Slot::Pile(pile_index) => {
+			let visible = &game.piles[pile_index].visible;
+			if visible.is_empty() {
+				return MouseTarget::EmptyPile(pile_index)
+			}
+			let depth = if slot == app.cursor { app.depth.min(visible.len()) } else { 1 };
+			let target_card_index = visible.len() - depth;
+			MouseTarget::PileCard{
+				pile_index,
+				n_cards: depth as u8,
+				target_card: visible[target_card_index],
+				target_card_index,
+				top: 0.,
+			}
+		}
+	}
+}
+
+pub fn foundation_suit(foundation_index: usize) -> Suit {
+	return *Suit::all().iter().find(|suit| suit.foundation_index() == foundation_index).unwrap()
+}
+
+// does what clicking on the slot would do: picks up the cards there, or puts down the cards already
+// picked up
+fn select_slot(app: &mut App, slot: Slot) {
+	if app.game.status() != GameStatus::InProgress {
+		return
+	}
+	let target = target(app, slot);
+	if app.game.move_in_progress.is_some() {
+		app.game.exec_move_in_progress(target);
+		return
+	}
+
+	let moves = match app.game.calc_moves(target) {
+		Some(moves) => moves,
+		None => {
+			app.message = Some("That can't go anywhere".to_string());
+			return
+		}
+	};
+	let to_foundation = moves.iter().find(|mv| matches!(mv, Move::CardMove{ dest: MoveDest::ToFoundation(_), .. }));
+	if moves.len() == 1 {
+		app.game.exec_move(moves[0]);
+	} else if let Some(mv) = to_foundation {
+		app.game.exec_move(*mv);
+	} else {
+		app.game.move_in_progress = Some(MoveInProgress{ target, moves });
+		app.message = Some("Where to? Pick a pile (1-7), or Esc to cancel".to_string());
+	}
+}
+
+// sends the picked up card (or the one under the cursor) to its foundation
+fn to_foundation(app: &mut App) {
+	if app.game.move_in_progress.is_none() {
+		let target = target(app, app.cursor);
+		match app.game.calc_moves(target).unwrap_or_default().into_iter().find(|mv| matches!(mv, Move::CardMove{ dest: MoveDest::ToFoundation(_), .. })) {
+			Some(mv) => { app.game.exec_move(mv); }
+			None => app.message = Some("That can't go on a foundation".to_string()),
+		}
+		return
+	}
+
+	let suit = app.game.move_in_progress.as_ref().and_then(|mip| mip.moves.iter().find_map(|mv| match mv {
+		Move::CardMove{ dest: MoveDest::ToFoundation(suit), .. } => Some(*suit),
+		_ => None,
+	}));
+	match suit {
+		Some(suit) => app.game.exec_move_in_progress(MouseTarget::Foundation(suit)),
+		None => {
+			app.game.move_in_progress = None;
+			app.message = Some("That can't go on a foundation".to_string());
+		}
+	}
+}
@@ -0,0 +1,184 @@
+// Draws the board as text. The stock, waste and foundations are along the top, with the piles in
+// columns underneath, the same as in the windowed game.
+
+use crate::{foundation_suit, App, Slot};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{cursor::MoveTo, queue};
+use solitaire_core::{time_text, Card, Colour, DrawCount, MouseTarget, Suit};
+use std::io::{self, Write};
+
+const COL_W: u16 = 6; // the width of each column of cards, in characters
+const TOP_ROW_Y: u16 = 2;
+const PILES_Y: u16 = 5; // the pile numbers are on this line, with the cards underneath
+const FOUNDATIONS_COL: usize = 3;
+
+const HIDDEN: &str = "▒▒▒";
+const EMPTY: &str = " · ";
+
+// how a card (or empty space) should stand out
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Highlight {
+	None,
+	Cursor, // under the cursor
+	PickedUp, // part of the move in progress
+}
+
+// the column a slot is drawn in
+pub fn column(slot: Slot) -> usize {
+	return match slot {
+		Slot::Stock => 0,
+		Slot::Waste => 1,
+		Slot::Foundation(i) => FOUNDATIONS_COL + i,
+		Slot::Pile(pile_index) => pile_index,
+	}
+}
+
+// the slot along the top that's above the given pile
+pub fn slot_above(pile_index: usize) -> Slot {
+	return match pile_index {
+		0 => Slot::Stock,
+		i if i < FOUNDATIONS_COL => Slot::Waste,
+		i => Slot::Foundation(i - FOUNDATIONS_COL),
+	}
+}
+
+fn suit_glyph(suit: Suit) -> char {
+	return match suit {
+		Suit::Diamonds => '♦',
+		Suit::Clubs => '♣',
+		Suit::Hearts => '♥',
+		Suit::Spades => '♠',
+	}
+}
+
+fn card_text(card: Card) -> String {
+	return format!("{:>2}{}", card.rank.letter(), suit_glyph(card.suit))
+}
+
+fn card_colour(card: Card) -> Color {
+	return match card.colour() {
+		Colour::Red => Color::Red,
+		Colour::Black => Color::Reset,
+	}
+}
+
+fn put(out: &mut impl Write, x: u16, y: u16, text: &str, colour: Color, highlight: Highlight) -> io::Result<()> {
+	queue!(out, MoveTo(x, y), SetForegroundColor(colour))?;
+	match highlight {
+		Highlight::None => {}
+		Highlight::Cursor => queue!(out, SetAttribute(Attribute::Reverse))?,
+		Highlight::PickedUp => queue!(out, SetBackgroundColor(Color::DarkYellow))?,
+	}
+	queue!(out, Print(text), SetAttribute(Attribute::Reset), ResetColor)?;
+	return Ok(())
+}
+
+fn put_card(out: &mut impl Write, col: usize, y: u16, card: Card, highlight: Highlight) -> io::Result<()> {
+	return put(out, 1 + col as u16 * COL_W, y, &card_text(card), card_colour(card), highlight)
+}
+
+fn put_space(out: &mut impl Write, col: usize, y: u16, text: &str, highlight: Highlight) -> io::Result<()> {
+	return put(out, 1 + col as u16 * COL_W, y, text, Color::DarkGrey, highlight)
+}
+
+// how the given slot should stand out, for the cards from `card_index` (counting up from the bottom
+// of the slot) onwards
+fn highlight(app: &App, slot: Slot, card_index: usize) -> Highlight {
+	let picked_up = match app.game.move_in_progress.as_ref().map(|mip| mip.target) {
+		Some(MouseTarget::StockTop) => slot == Slot::Waste,
+		Some(MouseTarget::Foundation(suit)) => slot == Slot::Foundation(suit.foundation_index()),
+		Some(MouseTarget::PileCard{ pile_index, target_card_index, .. }) => slot == Slot::Pile(pile_index) && card_index >= target_card_index,
+		_ => false,
+	};
+	if picked_up {
+		return Highlight::PickedUp
+	}
+
+	if slot != app.cursor {
+		return Highlight::None
+	}
+	return match slot {
+		Slot::Pile(pile_index) => {
+			let n_visible = app.game.piles[pile_index].visible.len();
+			match card_index + app.depth >= n_visible {
+				true => Highlight::Cursor,
+				false => Highlight::None,
+			}
+		}
+		_ => Highlight::Cursor,
+	}
+}
+
+pub fn draw(out: &mut impl Write, app: &App) -> io::Result<()> {
+	let game = &app.game;
+	queue!(out, Clear(ClearType::All))?;
+
+	let deal = match game.seed {
+		Some(seed) => format!("Deal #{}", seed),
+		None => "Custom deal".to_string(),
+	};
+	let draw = match game.rules.draw_count {
+		DrawCount::One => "Draw 1",
+		DrawCount::Three => "Draw 3",
+	};
+	let hud = format!("{}   {}   {}   Time: {}   Moves: {}   Undos: {}", deal, draw, game.score_text(), time_text(game.elapsed), game.moves, game.undos);
+	put(out, 1, 0, &hud, Color::Reset, Highlight::None)?;
+
+	// the stock, with the number of cards left in it underneath
+	let stock_text = if !game.stock.is_empty() { HIDDEN } else if game.can_recycle() { " ↺ " } else { EMPTY };
+	let stock_colour = if game.stock.is_empty() { Color::DarkGrey } else { Color::Blue };
+	put(out, 1, TOP_ROW_Y, stock_text, stock_colour, highlight(app, Slot::Stock, 0))?;
+	put_space(out, 0, TOP_ROW_Y + 1, &format!("{:>3}", game.stock.len()), Highlight::None)?;
+
+	// the waste, fanned out in Draw-3 so that the last few cards turned over can be seen
+	let n_shown = match game.rules.draw_count {
+		DrawCount::One => 1,
+		DrawCount::Three => 3,
+	};
+	let shown = &game.waste[game.waste.len().saturating_sub(n_shown)..];
+	if shown.is_empty() {
+		put_space(out, column(Slot::Waste), TOP_ROW_Y, EMPTY, highlight(app, Slot::Waste, 0))?;
+	}
+	for (i, card) in shown.iter().enumerate() {
+		let highlight = if i == shown.len() - 1 { highlight(app, Slot::Waste, 0) } else { Highlight::None };
+		put(out, 1 + column(Slot::Waste) as u16 * COL_W + i as u16 * 4, TOP_ROW_Y, &card_text(*card), card_colour(*card), highlight)?;
+	}
+
+	for i in 0..4 {
+		let slot = Slot::Foundation(i);
+		let suit = foundation_suit(i);
+		match game.foundation_top_card(suit) {
+			Some(card) => put_card(out, column(slot), TOP_ROW_Y, card, highlight(app, slot, 0))?,
+			None => put_space(out, column(slot), TOP_ROW_Y, &format!("  {}", suit_glyph(suit)), highlight(app, slot, 0))?,
+		}
+	}
+
+	// the piles, numbered so they can be picked with the number keys
+	let mut bottom = PILES_Y;
+	for (pile_index, pile) in game.piles.iter().enumerate() {
+		let slot = Slot::Pile(pile_index);
+		put_space(out, pile_index, PILES_Y, &format!("{:>2} ", pile_index + 1), Highlight::None)?;
+		let mut y = PILES_Y + 1;
+		if pile.is_empty() {
+			put_space(out, pile_index, y, EMPTY, highlight(app, slot, 0))?;
+		}
+		for _ in pile.hidden.iter() {
+			put(out, 1 + pile_index as u16 * COL_W, y, HIDDEN, Color::Blue, Highlight::None)?;
+			y += 1;
+		}
+		for (card_index, card) in pile.visible.iter().enumerate() {
+			put_card(out, pile_index, y, *card, highlight(app, slot, card_index))?;
+			y += 1;
+		}
+		bottom = bottom.max(y);
+	}
+
+	let y = bottom + 1;
+	if let Some(message) = &app.message {
+		put(out, 1, y, message, Color::Yellow, Highlight::None)?;
+	}
+	put(out, 1, y + 2, "Arrows: move   Enter/Space: select   1-7: pile   s: stock   w: waste   f: foundation   Esc: cancel", Color::DarkGrey, Highlight::None)?;
+	put(out, 1, y + 3, "u: undo   U: redo   a: auto-move   ?: hint   n: new deal   t: Draw-1/Draw-3   q: quit", Color::DarkGrey, Highlight::None)?;
+	return Ok(())
+}
@@ -0,0 +1,229 @@
+// Card movement animations.
+//
+// The game state always changes instantly; animations are purely cosmetic. Every frame the Animator
+// works out where each card should be drawn for the current game state. Any card whose place has
+// changed since the last frame (because of a move, an undo, a redo, a new deal...) gets a tween from
+// wherever it's currently being drawn to its new place, so all kinds of state changes are animated the
+// same way, and a new change partway through an animation just retargets the cards that are in flight.
+
+use crate::{draw_card, draw_card_w, foundation_x, n_fanned_waste_cards, pile_x, CARD_W, INSET, PILES_Y, PILE_CARD_V_OFFSET, WASTE_FAN_OFFSET, WASTE_X};
+use macroquad::prelude::*;
+use solitaire_core::{Card, Game, Suit};
+use std::collections::{HashMap, HashSet};
+
+const MOVE_DURATION: f32 = 0.25; // seconds for a card to travel to its new place at 1x speed
+const DEAL_STAGGER: f32 = 0.04; // seconds between each card leaving the stock in the initial deal at 1x speed
+const SPEEDS: [f32; 5] = [0., 0.5, 1., 2., 4.]; // the animation speeds the player can choose from. 0 turns animations off
+const DEFAULT_SPEED_INDEX: usize = 2;
+
+// where (and which way up) a card is drawn
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct CardPlace {
+	pos: Vec2,
+	face_up: bool,
+	z: usize, // cards with a higher z are drawn on top
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Tween {
+	from: CardPlace,
+	to: CardPlace,
+	delay: f32, // seconds to wait before starting to move
+	elapsed: f32, // seconds since the tween was created
+	duration: f32,
+}
+
+impl Tween {
+	// how far through the movement the card is, from 0 to 1
+	fn progress(&self) -> f32 {
+		if self.duration <= 0. {
+			return 1.
+		}
+		return ((self.elapsed - self.delay) / self.duration).clamp(0., 1.)
+	}
+
+	fn is_finished(&self) -> bool {
+		return self.elapsed >= self.delay + self.duration
+	}
+
+	// where the card is right now
+	fn current(&self) -> CardPlace {
+		let t = self.progress();
+		let eased = 1. - (1. - t).powi(3); // ease out, so the cards slow down as they land
+		return CardPlace {
+			pos: self.from.pos.lerp(self.to.pos, eased),
+			face_up: if t < 0.5 { self.from.face_up } else { self.to.face_up },
+			z: self.to.z,
+		}
+	}
+}
+
+pub struct Animator {
+	speed_index: usize, // index into SPEEDS
+	places: HashMap<Card, CardPlace>, // where every card belongs, as of the last sync
+	tweens: HashMap<Card, Tween>,
+}
+
+impl Animator {
+	pub fn new(game: &Game) -> Animator {
+		return Animator {
+			speed_index: DEFAULT_SPEED_INDEX,
+			places: layout(game),
+			tweens: HashMap::new(),
+		}
+	}
+
+	pub fn speed(&self) -> f32 {
+		return SPEEDS[self.speed_index]
+	}
+
+	pub fn faster(&mut self) {
+		self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+	}
+
+	pub fn slower(&mut self) {
+		self.speed_index = self.speed_index.saturating_sub(1);
+		if self.speed() == 0. {
+			self.skip();
+		}
+	}
+
+	// returns true while any card is still moving
+	pub fn is_busy(&self) -> bool {
+		return !self.tweens.is_empty()
+	}
+
+	// finishes every animation immediately
+	pub fn skip(&mut self) {
+		self.tweens.clear();
+	}
+
+	// the cards that are drawn by the animator rather than in their usual place on the board
+	pub fn flying_cards(&self) -> HashSet<Card> {
+		return self.tweens.keys().copied().collect()
+	}
+
+	// animates every card being dealt out from the stock, one at a time
+	pub fn deal(&mut self, game: &Game) {
+		self.tweens.clear();
+		self.places = layout(game);
+		if self.speed() == 0. {
+			return
+		}
+
+		let stock_place = CardPlace { pos: vec2(INSET, INSET), face_up: false, z: 0 };
+		let mut n_dealt = 0;
+		for pile in game.piles.iter() {
+			for card in pile.hidden.iter().chain(pile.visible.iter()) {
+				self.tweens.insert(*card, Tween {
+					from: stock_place,
+					to: self.places[card],
+					delay: n_dealt as f32 * DEAL_STAGGER / self.speed(),
+					elapsed: 0.,
+					duration: MOVE_DURATION / self.speed(),
+				});
+				n_dealt += 1;
+			}
+		}
+	}
+
+	// records that the given cards are currently being drawn at `pos` (eg. because they've been
+	// dragged there), so that they animate from there rather than from where they were picked up.
+	pub fn cards_moved_to(&mut self, cards: &[Card], pos: Vec2) {
+		for (i, card) in cards.iter().enumerate() {
+			self.tweens.remove(card);
+			if let Some(place) = self.places.get_mut(card) {
+				place.pos = pos + vec2(0., i as f32 * PILE_CARD_V_OFFSET);
+			}
+		}
+	}
+
+	// starts animating any cards whose place has changed since the last call
+	pub fn sync(&mut self, game: &Game) {
+		let new_places = layout(game);
+		for (card, to) in new_places.iter() {
+			let (from, changed) = match self.tweens.get(card) {
+				Some(tween) => (tween.current(), tween.to != *to),
+				None => (self.places[card], self.places[card] != *to),
+			};
+			if !changed {
+				continue
+			}
+			if self.speed() == 0. || (from.pos == to.pos && from.face_up == to.face_up) {
+				self.tweens.remove(card);
+				continue
+			}
+			self.tweens.insert(*card, Tween { from, to: *to, delay: 0., elapsed: 0., duration: MOVE_DURATION / self.speed() });
+		}
+		self.places = new_places;
+	}
+
+	pub fn update(&mut self, dt: f32) {
+		for tween in self.tweens.values_mut() {
+			tween.elapsed += dt;
+		}
+		self.tweens.retain(|_, tween| !tween.is_finished());
+	}
+
+	// draws the cards that are in flight, on top of the board
+	pub fn draw(&self, textures: &HashMap<Suit, Texture2D>) {
+		let mut flying: Vec<(Card, &Tween)> = self.tweens.iter().map(|(card, tween)| (*card, tween)).collect();
+		// cards still waiting to be dealt go underneath the ones that have set off, and stacks land in order
+		flying.sort_by(|(_, a), (_, b)| {
+			let a_waiting = a.elapsed < a.delay;
+			let b_waiting = b.elapsed < b.delay;
+			b_waiting.cmp(&a_waiting).then(a.to.z.cmp(&b.to.z))
+		});
+		for (card, tween) in flying {
+			let place = tween.current();
+			if tween.from.face_up == tween.to.face_up {
+				draw_card(&card, place.pos.x, place.pos.y, place.face_up, textures);
+			} else {
+				// flip the card over by squashing it to nothing and back out again
+				let t = tween.progress();
+				let w = CARD_W * (1. - 2.*t).abs();
+				draw_card_w(&card, place.pos.x + (CARD_W - w)*0.5, place.pos.y, w, place.face_up, textures);
+			}
+		}
+	}
+}
+
+// works out where every card belongs for the given game state
+fn layout(game: &Game) -> HashMap<Card, CardPlace> {
+	let mut places = HashMap::new();
+	let mut z = 0;
+	let mut place = |card: &Card, pos: Vec2, face_up: bool| {
+		places.insert(*card, CardPlace { pos, face_up, z });
+		z += 1;
+	};
+
+	for card in game.stock.iter() {
+		place(card, vec2(INSET, INSET), false);
+	}
+
+	// the cards below the fanned out ones are tucked away underneath the leftmost one
+	let first_fanned = game.waste.len() - n_fanned_waste_cards(game);
+	for (i, card) in game.waste.iter().enumerate() {
+		let x = WASTE_X + i.saturating_sub(first_fanned) as f32 * WASTE_FAN_OFFSET;
+		place(card, vec2(x, INSET), true);
+	}
+
+	for (pile_index, pile) in game.piles.iter().enumerate() {
+		let x = pile_x(pile_index);
+		for (i, card) in pile.hidden.iter().enumerate() {
+			place(card, vec2(x, PILES_Y + i as f32 * PILE_CARD_V_OFFSET), false);
+		}
+		for (i, card) in pile.visible.iter().enumerate() {
+			place(card, vec2(x, PILES_Y + (i + pile.hidden.len()) as f32 * PILE_CARD_V_OFFSET), true);
+		}
+	}
+
+	for suit in Suit::all() {
+		if let Some(top) = game.foundation_fill_levels.get(suit) {
+			for card in Card::all_cards().iter().filter(|card| card.suit == *suit && card.rank.index() <= top.index()) {
+				place(card, vec2(foundation_x(*suit), INSET), true);
+			}
+		}
+	}
+	return places
+}
@@ -1,6 +1,13 @@
+#![allow(clippy::needless_return)]
+
+mod animation;
+mod replay_viewer;
+
+use animation::Animator;
 use macroquad::prelude::*;
-use std::collections::{HashMap, VecDeque};
-use std::cmp::Ordering;
+use solitaire_core::{hints, time_text, Card, Cascade, CascadeLayout, Colour, DrawCount, Game, GameStatus, MouseTarget, Move, MoveDest, MoveInProgress, MoveSrc, PassLimit, Pile, Rank, Replay, Rules, SaveError, Scoring, Stats, Suit};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 
 #[macroquad::main("Solitaire")]
 async fn main() {
@@ -12,75 +19,346 @@ async fn main() {
 
 	request_new_screen_size(SCREEN_W, SCREEN_H);
 
+	// `--replay <file>` watches a replay instead of playing
+	if let Some(path) = arg_value("--replay") {
+		match Replay::load(Path::new(&path)) {
+			Ok(replay) => replay_viewer::run(replay, &textures).await,
+			Err(err) => eprintln!("Couldn't load the replay {}: {}", path, err),
+		}
+		return
+	}
+
 	// seed the RNG
 	let duration_since_epoch = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap();
 	rand::srand(duration_since_epoch.as_secs());
 
-	let mut game = Game::new();
+	// `--position <file>` plays a position written in the board notation. Otherwise, pick up where
+	// the player left off, if there's a saved game.
+	let (mut game, mut notice, is_new_game) = match arg_value("--position") {
+		Some(path) => match Game::load_position(Path::new(&path)) {
+			Ok(game) => (game, None, true),
+			Err(err) => {
+				eprintln!("Couldn't load the position {}: {}", path, err);
+				return
+			}
+		},
+		None => match load_saved_game() {
+			Ok(Some(game)) => (game, None, false),
+			Ok(None) => (new_game(Rules::default()), None, true),
+			Err(err) => {
+				let msg = format!("Couldn't resume the saved game ({}). Dealt a new game instead.", err);
+				eprintln!("{}", msg);
+				(new_game(Rules::default()), Some(Notice::new(msg)), true)
+			}
+		},
+	};
+	let mut rules = game.rules;
+	let mut animator = Animator::new(&game);
+	if is_new_game {
+		animator.deal(&game);
+	}
+
+	let mut stats = match load_stats() {
+		Ok(stats) => stats,
+		Err(err) => {
+			let msg = format!("Couldn't load your statistics ({}). Starting them afresh.", err);
+			eprintln!("{}", msg);
+			notice = Some(Notice::new(msg));
+			Stats::default()
+		}
+	};
+
+	// the game to switch to at the end of the frame, when a new one is dealt
+	let mut next_game: Option<Game> = None;
+
+	// input that arrives while cards are still moving waits here until they've landed
+	let mut queued_actions: VecDeque<Action> = VecDeque::new();
+
+	// the game is saved when the window is closed, so the close has to be handled manually
+	prevent_quit();
+
+	// the deal number being typed in, if the player is currently entering one
+	let mut deal_input: Option<String> = None;
+
+	// the hints for the current position, if the player has asked for them
+	let mut hint: Option<Hint> = None;
+
+	// the card(s) the mouse button was pressed on, while the button is held down
+	let mut drag: Option<Drag> = None;
+
+	// the bouncing cards shown after a win
+	let mut cascade: Option<Cascade> = None;
+
+	// the clock is stopped (and the board hidden) while paused
+	let mut paused = false;
+	let mut paused_while_away = false; // true if the game paused itself, rather than the player pressing P
+
+	// the statistics screen, which also pauses the game while it's shown
+	let mut show_stats = false;
+	let mut confirming_stats_reset = false;
 
 	loop {
 		clear_background(BG_COLOUR);
 
-		if is_key_down(KeyCode::Escape) {
+		if is_quit_requested() {
+			save_game(&game);
+			return;
+		}
+
+		// macroquad doesn't report the window losing focus (or being minimised), but on most platforms
+		// the game loop stalls while the window is minimised or hidden, so a very long frame means the
+		// player has probably been away. A window that's visible but in the background keeps running,
+		// so that isn't caught: the player has to press P.
+		if get_frame_time() > AWAY_FRAME_TIME && game.has_started() && game.status() == GameStatus::InProgress {
+			paused = true;
+			paused_while_away = true;
+		}
+
+		if let Some(input) = &mut deal_input {
+			while let Some(c) = get_char_pressed() {
+				if c.is_ascii_digit() && input.len() < DEAL_INPUT_MAX_LEN {
+					input.push(c);
+				}
+			}
+			if is_key_pressed(KeyCode::Backspace) {
+				input.pop();
+			} else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+				if let Ok(seed) = input.parse::<u64>() {
+					next_game = Some(Game::from_seed(seed, rules));
+				}
+				deal_input = None;
+			} else if is_key_pressed(KeyCode::Escape) {
+				deal_input = None;
+			}
+		} else if is_key_pressed(KeyCode::Escape) {
+			save_game(&game);
 			return;
+		} else if is_key_pressed(KeyCode::P) {
+			paused = !paused;
+			paused_while_away = false;
+		} else if is_key_pressed(KeyCode::I) {
+			show_stats = !show_stats;
+			confirming_stats_reset = false;
+		} else if show_stats && is_key_pressed(KeyCode::Delete) {
+			// resetting the stats takes two presses, in case of accidents
+			if confirming_stats_reset {
+				stats.reset();
+				save_stats(&stats);
+			}
+			confirming_stats_reset = !confirming_stats_reset;
+		} else if paused || show_stats {
+			// nothing else can be done while paused
 		} else if is_key_pressed(KeyCode::Space) {
-			game.exec_move(Move::CycleStock);
+			queued_actions.push_back(Action::DrawStock);
+		} else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+			skip_animations(&mut game, &mut animator, &mut queued_actions);
+			if let Some(c) = &mut cascade {
+				c.finish();
+			}
+		} else if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+			animator.slower();
+			notice = Some(Notice::new(animation_speed_text(&animator)));
+		} else if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+			animator.faster();
+			notice = Some(Notice::new(animation_speed_text(&animator)));
 		} else if is_key_pressed(KeyCode::R) {
-			game = Game::new();
+			next_game = Some(new_game(rules));
+		} else if is_key_pressed(KeyCode::T) {
+			// switching between draw-1 and draw-3 starts a new game with the new rules
+			rules.draw_count = match rules.draw_count {
+				DrawCount::One => DrawCount::Three,
+				DrawCount::Three => DrawCount::One,
+			};
+			next_game = Some(new_game(rules));
+		} else if is_key_pressed(KeyCode::L) {
+			// likewise for changing the number of passes through the stock
+			rules.pass_limit = match rules.pass_limit {
+				PassLimit::Unlimited => PassLimit::Passes(1),
+				PassLimit::Passes(1) => PassLimit::Passes(3),
+				PassLimit::Passes(_) => PassLimit::Unlimited,
+			};
+			next_game = Some(new_game(rules));
+		} else if is_key_pressed(KeyCode::V) {
+			// and for switching between standard and Vegas scoring
+			rules.scoring = match rules.scoring {
+				Scoring::Standard => Scoring::Vegas,
+				Scoring::Vegas => Scoring::Standard,
+			};
+			next_game = Some(new_game(rules));
+		} else if is_key_pressed(KeyCode::S) {
+			next_game = Some(game.restarted());
 		} else if is_key_pressed(KeyCode::D) {
-			game.debug();
+			// prints the board in the notation, eg. for pasting into a bug report
+			print!("{}", game);
+		} else if is_redo_pressed() {
+			queued_actions.push_back(Action::Redo);
 		} else if is_key_pressed(KeyCode::U) {
-			game.undo_move();
+			queued_actions.push_back(Action::Undo);
 		} else if is_key_pressed(KeyCode::A) {
-			game.auto_move();
+			queued_actions.push_back(Action::AutoMove);
+		} else if is_key_pressed(KeyCode::N) {
+			deal_input = Some(String::new());
+		} else if is_key_pressed(KeyCode::H) {
+			// pressing H again on the same position cycles through the alternatives
+			hint = match hint {
+				Some(h) if h.is_for(&game) => Some(h.next()),
+				_ => Some(Hint::new(&game)),
+			};
+		}
+
+		// the board is hidden while paused, so that it can't be studied with the clock stopped
+		if paused || show_stats {
+			if is_mouse_button_pressed(MouseButton::Left) {
+				paused = false;
+				show_stats = false;
+			}
+			while get_char_pressed().is_some() {}
+			if show_stats {
+				draw_stats(&stats, game.rules, confirming_stats_reset);
+			} else {
+				draw_paused(paused_while_away);
+			}
+			draw_hud(&game);
+			next_frame().await;
+			continue
+		}
+
+		if is_mouse_button_pressed(MouseButton::Right) {
+			skip_animations(&mut game, &mut animator, &mut queued_actions);
+			if let Some(c) = &mut cascade {
+				c.finish();
+			}
+		}
+
+		// a new deal replaces everything that was going on in the old one. An unfinished game counts as a loss
+		if let Some(mut next) = next_game.take() {
+			if stats.record(&mut game) {
+				save_stats(&stats);
+			}
+			save_replay(&mut game, true);
+			if game.rules.scoring == Scoring::Vegas && next.rules.scoring == Scoring::Vegas {
+				next.carried_score = game.total_score();
+			}
+			game = next;
+			queued_actions.clear();
+			drag = None;
+			animator.deal(&game);
 		}
 
-		draw_game(&game, &textures);
+		// carry out the queued input one action at a time, once the previous action's cards have landed
+		animator.update(get_frame_time());
+		if !animator.is_busy() {
+			if let Some(action) = queued_actions.pop_front() {
+				apply_action(&mut game, action);
+				if let Action::AutoMove = action {
+					if game.is_looping() {
+						notice = Some(Notice::new("No progress possible: auto-move is going round in circles. Press R for a new deal".to_string()));
+					}
+				}
+			}
+		}
+		animator.sync(&game);
 
+		// hints are only shown until the position changes
+		if hint.as_ref().is_some_and(|h| !h.is_for(&game)) {
+			hint = None;
+		}
+
+		// throw away any typed characters that weren't consumed by the deal number input
+		if deal_input.is_none() {
+			while get_char_pressed().is_some() {}
+		}
+
+		game.tick(get_frame_time() as f64);
+		let status = game.status();
+		if status == GameStatus::Won && stats.record(&mut game) {
+			save_stats(&stats);
+		}
+		if status == GameStatus::Won {
+			save_replay(&mut game, false);
+		}
+
+		// the board can't be clicked on once the game is over, and clicks made while cards are moving
+		// are saved for later, as the cards under the mouse might not be where they appear to be
 		let (mx, my) = mouse_position();
-		if let Some(target) = game.mouse_hit(mx, my) {
-			draw_mouse_hit(target, MOUSE_TARGET_COLOUR);
-			if is_mouse_button_pressed(MouseButton::Left) {
-				println!("target: {:?}", target);
-				if Option::is_some(&game.move_in_progress) {
-					game.exec_move_in_progress(target);
-				} else {
-					game.move_in_progress = None;
-					if let Some(moves) = game.calc_moves(target) {
-						println!("moves: {:?}", moves);
-
-						if moves.len() == 1 {
-							game.exec_move(moves[0]);
-						}
-
-						// if there's a move to a foundation, do it
-						else if let mv@Move::CardMove{ dest: MoveDest::ToFoundation(..), .. } = moves[0] {
-							game.exec_move(mv);
-						}
-
-						else {
-							// if the only legal moves are from stock to piles, just pick one
-							let moves_all_from_stock_to_piles = moves.clone().into_iter().fold(true, |z, mv| {
-								z && match mv {
-									Move::CardMove{ src: MoveSrc::FromStock, dest: MoveDest::ToPile(..), .. } => true,
-									_ => false
-								}
-							});
-							if moves_all_from_stock_to_piles {
-								game.exec_move(moves[0]);
-							} else {
-								game.move_in_progress = Some(MoveInProgress{ target, moves });
-							}
-
-						}
-					} else {
-						println!("No moves");
+		let busy = animator.is_busy() || !queued_actions.is_empty();
+		let target = if status == GameStatus::InProgress && !busy { mouse_hit(&game, mx, my) } else { None };
+		if is_mouse_button_pressed(MouseButton::Left) {
+			if busy {
+				queued_actions.push_back(Action::Click(vec2(mx, my)));
+			} else {
+				drag = target.map(|target| Drag::new(&game, target, vec2(mx, my)));
+			}
+		}
+		if let Some(d) = &mut drag {
+			if is_mouse_button_down(MouseButton::Left) {
+				d.update(vec2(mx, my));
+			} else {
+				// the button was released: either drop the cards or treat it as a click
+				if d.is_dragging {
+					// the cards fly from where they were dropped, either to their destination or back home
+					animator.cards_moved_to(&d.cards, d.cards_pos());
+					if let Some(mv) = d.drop_move(&game) {
+						game.exec_move(mv);
 					}
+					game.move_in_progress = None;
+				} else {
+					click(&mut game, d.target);
+				}
+				drag = None;
+			}
+		}
+
+		// once the last card has landed on its foundation, celebrate the win. clicking skips to the end
+		let is_cascading = cascade.as_ref().is_some_and(|c| !c.is_finished());
+		if status == GameStatus::Won && !busy {
+			let c = cascade.get_or_insert_with(|| Cascade::new(cascade_layout(), game.seed.unwrap_or(0)));
+			if is_mouse_button_pressed(MouseButton::Left) {
+				c.finish();
+			} else {
+				c.update(get_frame_time() as f64);
+			}
+		} else {
+			cascade = None;
+		}
+
+		let mut flying = animator.flying_cards();
+		if let Some(c) = &cascade {
+			flying.extend(c.cards().iter().map(|cc| cc.card));
+		}
+		match &drag {
+			Some(d) if d.is_dragging => draw_drag(&game, d, &flying, &textures),
+			_ => {
+				draw_game(&game, &flying, &textures);
+				if let Some(target) = target {
+					draw_mouse_hit(&game, target, MOUSE_TARGET_COLOUR);
 				}
 			}
 		}
+		animator.draw(&textures);
+		if let Some(c) = &cascade {
+			draw_cascade(c, &textures);
+		}
+		if let Some(h) = &hint {
+			draw_hint(&game, h);
+		}
+		draw_deal_number(&game, deal_input.as_deref());
+		draw_hud(&game);
+		if notice.as_ref().is_some_and(|n| n.has_expired()) {
+			notice = None;
+		}
+		if let Some(n) = &notice {
+			draw_text(&n.text, INSET, screen_height() - HUD_H - STATUS_LINE_H*0.5, STATUS_FONT_SIZE, BLACK);
+		}
 
-		// TODO detect win condition
+		// let the last cards land (and bounce away) before showing the result
+		if status != GameStatus::InProgress && !busy && !is_cascading {
+			match game_over_overlay(&game, status) {
+				Some(GameOverChoice::NewGame) => next_game = Some(new_game(rules)),
+				Some(GameOverChoice::ReplayDeal) => next_game = Some(game.restarted()),
+				None => {}
+			}
+		}
 
 		next_frame().await;
 	}
@@ -89,7 +367,6 @@ async fn main() {
 const BG_COLOUR: Color = Color::new(0.25, 0.7, 0.3, 1.);
 const SCREEN_H: f32 = 500.;
 const SCREEN_W: f32 = SCREEN_H*1.2;
-const N_PILES: u8 = 7; // number of piles
 const INSET: f32 = 30.; // distance from edge of screen to the cards
 const FOUNDATIONS_X: f32 = INSET+3.*PILE_H_OFFSET; // the leftmost x-coord of the foundation piles
 const CARD_W: f32 = SCREEN_H*0.2; // card width
@@ -105,957 +382,775 @@ const PILE_CARD_V_OFFSET: f32 = CARD_FONT_SIZE*0.6; // vertical distance between
 const PILE_H_OFFSET: f32 = CARD_W * 1.5; // horizontal distance between the left edge of adjacent piles
 const MOUSE_TARGET_COLOUR: Color = Color::new(1.00, 0.00, 1.00, 0.1);
 const MOVE_IN_PROGRESS_COLOUR: Color = Color::new(0.00, 1.00, 1.00, 0.5);
+const DROP_ZONE_COLOUR: Color = Color::new(0.00, 1.00, 0.00, 0.3);
+const DRAG_THRESHOLD: f32 = 5.; // how far (in pixels) the mouse has to move with the button down to start dragging
+const HINT_SRC_COLOUR: Color = Color::new(1.00, 0.85, 0.00, 0.5);
+const HINT_DEST_COLOUR: Color = Color::new(1.00, 0.50, 0.00, 0.5);
+const WASTE_X: f32 = INSET + PILE_H_OFFSET; // the leftmost x-coord of the waste
+const WASTE_FAN_OFFSET: f32 = CARD_W*0.2; // horizontal distance between fanned cards on the waste
+const STATUS_X: f32 = WASTE_X + CARD_W + 2.*WASTE_FAN_OFFSET + INSET*0.5; // the leftmost x-coord of the status text (between the waste and foundations)
+const STATUS_FONT_SIZE: f32 = 20.;
+const STATUS_LINE_H: f32 = STATUS_FONT_SIZE*1.2; // vertical distance between lines of status text
+const OVERLAY_COLOUR: Color = Color::new(0.00, 0.00, 0.00, 0.6);
+const OVERLAY_TITLE_FONT_SIZE: f32 = 60.;
+const OVERLAY_BUTTON_W: f32 = 220.;
+const OVERLAY_BUTTON_H: f32 = 50.;
+const OVERLAY_BUTTON_COLOUR: Color = Color::new(0.95, 0.95, 0.95, 1.);
+const OVERLAY_BUTTON_HOVER_COLOUR: Color = Color::new(0.75, 0.95, 1.00, 1.);
+const NOTICE_DURATION: f64 = 8.; // seconds
+const DEAL_INPUT_MAX_LEN: usize = 20; // u64::MAX has 20 digits
+const HUD_H: f32 = STATUS_LINE_H*1.5; // height of the strip along the bottom of the screen
+const HUD_COLOUR: Color = Color::new(0.00, 0.00, 0.00, 0.5);
+const STATS_COLUMN_W: f32 = 110.; // the width of each column of numbers on the stats screen
+const AWAY_FRAME_TIME: f32 = 1.; // seconds. a frame taking longer than this pauses the game (even if it was just slow)
+
+fn draw_mouse_hit(game: &Game, target: MouseTarget, col:Color) {
+	let rect = target_rect(game, target);
+	draw_rectangle(rect.x, rect.y, rect.w, rect.h, col);
+}
 
-fn draw_mouse_hit(target: MouseTarget, col:Color) {
-	match target {
-		MouseTarget::StockTop => {
-			draw_rectangle(INSET + PILE_H_OFFSET, INSET, CARD_W, CARD_H, col);
-		}
-		MouseTarget::StockDeck => {
-			draw_rectangle(INSET, INSET, CARD_W, CARD_H, col);
-		}
-		MouseTarget::Foundation(suit) => {
-			draw_rectangle(FOUNDATIONS_X+suit.foundation_offset()*PILE_H_OFFSET, INSET, CARD_W, CARD_H, col);
-		}
-		MouseTarget::EmptyPile(pile_index) => {
-			let x = Pile::pile_x(pile_index);
-			let y = PILES_Y;
-			let w = CARD_W;
-			let h = CARD_H;
-			draw_rectangle(x, y, w, h, col);
-		}
+// the area of the screen covered by the given target
+fn target_rect(game: &Game, target: MouseTarget) -> Rect {
+	return match target {
+		MouseTarget::StockTop => Rect::new(waste_top_x(game), INSET, CARD_W, CARD_H),
+		MouseTarget::StockDeck | MouseTarget::Recycle => Rect::new(INSET, INSET, CARD_W, CARD_H),
+		MouseTarget::Foundation(suit) => Rect::new(foundation_x(suit), INSET, CARD_W, CARD_H),
+		MouseTarget::EmptyPile(pile_index) => Rect::new(pile_x(pile_index), PILES_Y, CARD_W, CARD_H),
 		MouseTarget::PileCard{pile_index, n_cards, top, ..} => {
-			let x = Pile::pile_x(pile_index);
-			let y = top;
-			let w = CARD_W;
 			let h = CARD_H + PILE_CARD_V_OFFSET*((n_cards-1) as f32);
-			draw_rectangle(x, y, w, h, col);
+			Rect::new(pile_x(pile_index), top, CARD_W, h)
 		}
 	}
 }
 
-fn draw_game(game: &Game, textures:&HashMap<Suit, Texture2D>) {
-	// draw stock
-	if game.stock.len() > 1 {
-		draw_card(&Card::new(Suit::Diamonds, Rank::Ace), INSET, INSET, false, textures);
-	}
-	if !game.stock.is_empty() {
-		draw_card(&game.stock[0], INSET + PILE_H_OFFSET, INSET, true, textures);
-	}
+// the card(s) being dragged with the mouse. A drag only really starts once the mouse has moved a
+// little way, so that clicking without dragging still works.
+struct Drag {
+	target: MouseTarget, // where the mouse button was pressed
+	cards: Vec<Card>, // the cards being carried, from the bottom of the stack to the top
+	moves: Vec<Move>, // the moves that could be made by dropping the cards somewhere
+	grab_offset: Vec2, // the mouse position relative to the top-left corner of the carried cards
+	start: Vec2, // where the mouse was when the button was pressed
+	pos: Vec2, // where the mouse is now
+	is_dragging: bool,
+}
 
-	// draw piles
-	for (i, pile) in game.piles[..].into_iter().enumerate() {
-		let x = Pile::pile_x(i);
-		draw_pile(pile, x, PILES_Y, textures);
+impl Drag {
+	fn new(game: &Game, target: MouseTarget, pos: Vec2) -> Drag {
+		let (cards, origin) = match target {
+			MouseTarget::StockTop => (game.waste.last().copied().into_iter().collect(), vec2(waste_top_x(game), INSET)),
+			MouseTarget::Foundation(suit) => (game.foundation_top_card(suit).into_iter().collect(), vec2(foundation_x(suit), INSET)),
+			MouseTarget::PileCard{ pile_index, target_card_index, top, .. } => {
+				(game.piles[pile_index].visible[target_card_index..].to_vec(), vec2(pile_x(pile_index), top))
+			}
+			MouseTarget::StockDeck | MouseTarget::Recycle | MouseTarget::EmptyPile(_) => (Vec::new(), pos),
+		};
+		let moves = game.calc_moves(target).unwrap_or_default().into_iter()
+			.filter(|mv| matches!(mv, Move::CardMove{..}))
+			.collect();
+		return Drag { target, cards, moves, grab_offset: pos - origin, start: pos, pos, is_dragging: false }
 	}
 
-	// draw foundations
-	for suit in Suit::all() {
-		draw_foundation(suit.clone(), game.foundation_fill_levels.get(&suit), FOUNDATIONS_X+suit.foundation_offset()*PILE_H_OFFSET, INSET, textures);
+	fn update(&mut self, pos: Vec2) {
+		self.pos = pos;
+		if !self.cards.is_empty() && self.start.distance(pos) > DRAG_THRESHOLD {
+			self.is_dragging = true;
+		}
 	}
 
-	// draw move_in_progress
-	if let Some(mip) = &game.move_in_progress {
-		draw_mouse_hit(mip.target, MOVE_IN_PROGRESS_COLOUR);
+	// where the top-left corner of the carried cards is drawn
+	fn cards_pos(&self) -> Vec2 {
+		return self.pos - self.grab_offset
 	}
-}
 
-fn draw_foundation(suit: Suit, rank: Option<&Rank>, x:f32, y:f32, textures:&HashMap<Suit, Texture2D>) {
-	match rank {
-		Some(r) => {
-			draw_card(&Card::new(suit, r.to_owned()), x, y, true, textures);
-		}
-		None => {
-			draw_rectangle_lines(x, y, CARD_W, CARD_H, CARD_BORDER_WIDTH, BLACK);
-			let tex = *textures.get(&suit).unwrap();
-			let col = Color::new(0., 0., 0., 0.7);
-			draw_texture_ex(tex, x+CARD_W*0.5-CARD_SUIT_TEX_SIZE_FOUNDATION*0.5, y+CARD_H*0.5-CARD_SUIT_TEX_SIZE_FOUNDATION*0.63, col, DrawTextureParams{
-				dest_size: Some(vec2(CARD_SUIT_TEX_SIZE_FOUNDATION, CARD_SUIT_TEX_SIZE_FOUNDATION)),
-				..Default::default()
-			});
+	// returns the move whose destination is most overlapped by the carried cards, if any
+	fn drop_move(&self, game: &Game) -> Option<Move> {
+		let pos = self.cards_pos();
+		let dragged = Rect::new(pos.x, pos.y, CARD_W, CARD_H);
+		let mut best: Option<(f32, Move)> = None;
+		for mv in self.moves.iter() {
+			if let Move::CardMove{ dest, .. } = mv {
+				let area = match dragged.intersect(target_rect(game, dest_target(game, *dest))) {
+					Some(overlap) => overlap.w * overlap.h,
+					None => continue,
+				};
+				if best.is_none_or(|(best_area, _)| area > best_area) {
+					best = Some((area, *mv));
+				}
+			}
 		}
+		return best.map(|(_, mv)| mv)
 	}
 }
 
-fn draw_pile(pile: &Pile, x:f32, y:f32, textures:&HashMap<Suit, Texture2D>) {
-	if pile.is_empty() {
-		draw_rectangle_lines(x, y, CARD_W, CARD_H, CARD_BORDER_WIDTH, BLACK);
-		return
+// draws the board with the carried cards under the mouse, and highlights where they can be dropped
+fn draw_drag(game: &Game, drag: &Drag, flying: &HashSet<Card>, textures:&HashMap<Suit, Texture2D>) {
+	// draw the board as if the carried cards had already been picked up
+	let mut view = game.clone();
+	match drag.target {
+		MouseTarget::StockTop => { view.waste.pop(); }
+		MouseTarget::Foundation(suit) => { view.pop_foundation(suit); }
+		MouseTarget::PileCard{ pile_index, target_card_index, .. } => view.piles[pile_index].visible.truncate(target_card_index),
+		MouseTarget::StockDeck | MouseTarget::Recycle | MouseTarget::EmptyPile(_) => {}
 	}
+	view.move_in_progress = None;
+	draw_game(&view, flying, textures);
 
-	for (i, card) in pile.hidden[..].into_iter().enumerate() {
-		draw_card(card, x, y + i as f32 * PILE_CARD_V_OFFSET, false, textures);
-	}
-	let n_hidden = pile.hidden.len() as f32;
-	for (i, card) in pile.visible[..].into_iter().enumerate() {
-		draw_card(card, x, y + (i as f32 + n_hidden) * PILE_CARD_V_OFFSET, true, textures);
+	for mv in drag.moves.iter() {
+		if let Move::CardMove{ dest, .. } = mv {
+			draw_mouse_hit(game, dest_target(game, *dest), DROP_ZONE_COLOUR);
+		}
 	}
-}
 
-fn draw_card(c: &Card, x:f32, y:f32, visible:bool, textures:&HashMap<Suit, Texture2D>) {
-	draw_rectangle(x, y, CARD_W, CARD_H, WHITE);
-	draw_rectangle_lines(x, y, CARD_W, CARD_H, CARD_BORDER_WIDTH, BLACK);
-
-	if visible {
-		let col = c.col();
-		draw_text(c.rank.letter(), x, y + CARD_FONT_SIZE*0.55, CARD_FONT_SIZE, col);
-		draw_texture_ex(*textures.get(&c.suit).unwrap(), x+CARD_W*0.63, y+CARD_H*0.02, col, DrawTextureParams{
-			dest_size: Some(vec2(CARD_SUIT_TEX_SIZE_PILES, CARD_SUIT_TEX_SIZE_PILES)),
-			..Default::default()
-		});
-	} else {
-		draw_rectangle(
-			x+CARD_BACK_WHITE_BORDER_MARGIN, y+CARD_BACK_WHITE_BORDER_MARGIN,
-			CARD_W-2.*CARD_BACK_WHITE_BORDER_MARGIN, CARD_H-2.*CARD_BACK_WHITE_BORDER_MARGIN, CARD_BACK_COLOUR);
+	let pos = drag.cards_pos();
+	for (i, card) in drag.cards.iter().enumerate() {
+		draw_card(card, pos.x, pos.y + i as f32 * PILE_CARD_V_OFFSET, true, textures);
 	}
 }
 
-struct Game {
-	stock: VecDeque<Card>,
-	piles: Vec<Pile>,
-	foundation_fill_levels: HashMap<Suit, Rank>,
-	move_in_progress: Option<MoveInProgress>,
-	move_history: Vec<Move>,
+// the suggested moves for a position, and which one is currently being shown
+struct Hint {
+	moves: Vec<Move>,
+	index: usize,
+	position_hash: u64, // the position the hint was asked for
+	rules: Rules,
 }
 
-impl Game {
-	pub fn new() -> Game {
-		let mut cards = Card::all_cards().to_vec();
-		shuffle(&mut cards);
-
-		let mut game = Game {
-			stock: VecDeque::from(cards),
-			piles: Vec::new(),
-			foundation_fill_levels: HashMap::new(),
-			move_in_progress: None,
-			move_history: Vec::new(),
-		};
-
-		for pile_size in 1..=N_PILES {
-			let mut pile = Pile::new();
-			for i in 0..pile_size {
-				let card = game.stock.pop_front().unwrap();
-				if i == pile_size - 1 {
-					pile.visible.push(card);
-				} else {
-					pile.hidden.push(card);
-				}
-			}
-			game.piles.push(pile);
+impl Hint {
+	fn new(game: &Game) -> Hint {
+		return Hint {
+			moves: hints(game),
+			index: 0,
+			position_hash: game.position_hash(),
+			rules: game.rules,
 		}
+	}
 
-		return game;
+	// returns true if the hint was calculated for the game's current position
+	fn is_for(&self, game: &Game) -> bool {
+		return self.position_hash == game.position_hash() && self.rules == game.rules
 	}
 
-	pub fn mouse_hit(&self, mx:f32, my:f32) -> Option<MouseTarget> {
-		// check stock
-		if !self.stock.is_empty() && Card::mouse_hit(INSET + PILE_H_OFFSET, INSET, mx, my) {
-			return Some(MouseTarget::StockTop)
-		}
-		if !self.stock.len() > 1 && Card::mouse_hit(INSET, INSET, mx, my) {
-			return Some(MouseTarget::StockDeck)
-		}
+	fn next(self) -> Hint {
+		let index = if self.moves.is_empty() { 0 } else { (self.index + 1) % self.moves.len() };
+		return Hint { index, ..self }
+	}
 
-		// check foundations
-		for suit in Suit::all() {
-			if Card::mouse_hit(FOUNDATIONS_X+suit.foundation_offset()*PILE_H_OFFSET, INSET, mx, my) {
-				return Some(MouseTarget::Foundation(suit.clone()))
-			}
+	fn current(&self) -> Option<Move> {
+		return self.moves.get(self.index).copied()
+	}
+}
+
+fn draw_hint(game: &Game, hint: &Hint) {
+	let mv = match hint.current() {
+		Some(mv) => mv,
+		None => {
+			draw_text("No hints available", STATUS_X, INSET + 4.*STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
+			return
 		}
+	};
+	let (src, dest) = move_targets(game, mv);
+	draw_mouse_hit(game, src, HINT_SRC_COLOUR);
+	if let Some(dest) = dest {
+		draw_mouse_hit(game, dest, HINT_DEST_COLOUR);
+	}
+	let text = format!("Hint {}/{} (H for next)", hint.index + 1, hint.moves.len());
+	draw_text(&text, STATUS_X, INSET + 4.*STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
+}
 
-		// check piles
-		for (pile_index, pile) in self.piles[..].into_iter().enumerate() {
-			let x = Pile::pile_x(pile_index);
-			if mx < x || mx > x+CARD_W {continue}
-
-			// check visible cards in reverse order
-			let n_hidden = pile.hidden.len() as f32;
-			for (card_index, card) in pile.visible[..].into_iter().enumerate().rev() {
-				let y = PILES_Y + (card_index as f32 + n_hidden) * PILE_CARD_V_OFFSET;
-				if Card::mouse_hit(x, y, mx, my) {
-					return Some(MouseTarget::PileCard{
-						pile_index,
-						n_cards: (pile.visible.len() - card_index) as u8,
-						target_card: *card,
-						target_card_index: card_index,
-						top: y,
-					})
-				}
-			}
+// returns the targets that would be clicked to make the given move: where it's from and, for card
+// moves, where it goes to.
+fn move_targets(game: &Game, mv: Move) -> (MouseTarget, Option<MouseTarget>) {
+	return match mv {
+		Move::DrawStock{..} => (MouseTarget::StockDeck, None),
+		Move::RecycleWaste => (MouseTarget::Recycle, None),
+		Move::CardMove{ card, src, dest } => {
+			let src_target = match src {
+				MoveSrc::FromStock => MouseTarget::StockTop,
+				MoveSrc::FromFoundation(suit) => MouseTarget::Foundation(suit),
+				MoveSrc::FromPile{ pile_index, n_cards, target_card_index, .. } => MouseTarget::PileCard{
+					pile_index,
+					n_cards,
+					target_card: card,
+					target_card_index,
+					top: pile_card_y(&game.piles[pile_index], target_card_index),
+				},
+			};
+			(src_target, Some(dest_target(game, dest)))
+		}
+	}
+}
 
-			if pile.is_empty() && Card::mouse_hit(x, PILES_Y, mx, my) {
-				return Some(MouseTarget::EmptyPile(pile_index))
+// the target that would be clicked to move a card to the given destination
+fn dest_target(game: &Game, dest: MoveDest) -> MouseTarget {
+	return match dest {
+		MoveDest::ToFoundation(suit) => MouseTarget::Foundation(suit),
+		MoveDest::ToPile(pile_index) => {
+			let pile = &game.piles[pile_index];
+			match pile.top_card() {
+				Some(top) => MouseTarget::PileCard{
+					pile_index,
+					n_cards: 1,
+					target_card: top,
+					target_card_index: pile.visible.len() - 1,
+					top: pile_card_y(pile, pile.visible.len() - 1),
+				},
+				None => MouseTarget::EmptyPile(pile_index),
 			}
 		}
-
-		return None
 	}
+}
 
-	pub fn foundation_top_card(&self, suit:Suit) -> Option<Card> {
-		let rank = self.foundation_fill_levels.get(&suit)?;
-		Some(Card::new(suit, *rank))
+// draws the board, leaving out the cards that are in flight (which are drawn by the animator)
+fn draw_game(game: &Game, flying: &HashSet<Card>, textures:&HashMap<Suit, Texture2D>) {
+	// draw stock
+	if let Some(card) = game.stock.iter().rev().find(|card| !flying.contains(card)) {
+		draw_card(card, INSET, INSET, false, textures);
+	} else {
+		draw_rectangle_lines(INSET, INSET, CARD_W, CARD_H, CARD_BORDER_WIDTH, BLACK);
+		// a ring shows that the waste can be turned back over
+		if game.can_recycle() {
+			draw_circle_lines(INSET + CARD_W*0.5, INSET + CARD_H*0.5, CARD_W*0.3, CARD_BORDER_WIDTH*2., BLACK);
+		}
+	}
+	let pass_text = match game.rules.pass_limit {
+		PassLimit::Unlimited => format!("Pass {}", game.passes),
+		PassLimit::Passes(limit) => format!("Pass {}/{}", game.passes, limit),
+	};
+	draw_text(&pass_text, INSET, INSET + CARD_H + STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
+
+	// draw waste, with the top few cards fanned out
+	let n_fanned = n_fanned_waste_cards(game);
+	let fanned = &game.waste[game.waste.len() - n_fanned..];
+	for (i, card) in fanned.iter().enumerate() {
+		if !flying.contains(card) {
+			draw_card(card, WASTE_X + i as f32 * WASTE_FAN_OFFSET, INSET, true, textures);
+		}
 	}
 
-	pub fn calc_moves(&self, target:MouseTarget) -> Option<Vec<Move>> {
-		let mut moves: Vec<Move> = Vec::new();
-
-		match target {
-			MouseTarget::StockTop => {
-				let card = self.stock.front()?;
-
-				if card.rank == Rank::Ace {
-					moves.push(Move::CardMove{
-						card: *card,
-						src: MoveSrc::FromStock,
-						dest: MoveDest::ToFoundation(card.suit),
-					})
-				}
+	// draw piles
+	for (i, pile) in game.piles.iter().enumerate() {
+		let x = pile_x(i);
+		draw_pile(pile, x, PILES_Y, flying, textures);
+	}
 
-				// consider moves to the foundation
-				for suit in Suit::all() {
-					if let Some(top) = self.foundation_top_card(*suit) {
-						if card.can_stack_onto_in_foundation(top) {
-							moves.push(Move::CardMove{
-								card: *card,
-								src: MoveSrc::FromStock,
-								dest: MoveDest::ToFoundation(card.suit),
-							});
-							break;
-						}
-					}
-				}
+	// draw foundations, showing the highest card that has already landed
+	for suit in Suit::all() {
+		let mut rank = game.foundation_fill_levels.get(suit).copied();
+		while let Some(r) = rank.filter(|r| flying.contains(&Card::new(*suit, *r))) {
+			rank = r.pred();
+		}
+		draw_foundation(*suit, rank.as_ref(), foundation_x(*suit), INSET, textures);
+	}
 
-				// consider moves to the piles
-				for (i, pile) in self.piles[..].into_iter().enumerate() {
-					// if the card can go onto the top visible card, it's a valid move
-					if let Some(top) = pile.top_card() {
-						if card.can_pile_onto(top) {
-							moves.push(Move::CardMove{
-								card: *card,
-								src: MoveSrc::FromStock,
-								dest: MoveDest::ToPile(i),
-							});
-						}
-					}
+	// draw move_in_progress
+	if let Some(mip) = &game.move_in_progress {
+		draw_mouse_hit(game, mip.target, MOVE_IN_PROGRESS_COLOUR);
+	}
+}
 
-					else if pile.is_empty() && card.rank == Rank::King {
-						// if the pile is empty and the card is a king, it's a valid move
-						moves.push(Move::CardMove{
-							card: *card,
-							src: MoveSrc::FromStock,
-							dest: MoveDest::ToPile(i),
-						});
-					}
-				}
-			}
-			MouseTarget::StockDeck => {
-				if self.stock.len() > 1 {
-					moves.push(Move::CycleStock);
-				}
-			}
-			MouseTarget::Foundation(suit) => {
-				let card = self.foundation_top_card(suit)?;
-
-				// consider moves to the piles
-				for (i, pile) in self.piles[..].into_iter().enumerate() {
-					// if the card can go onto the top visible card, it's a valid move
-					if let Some(top) = pile.top_card() {
-						if card.can_pile_onto(top) {
-							moves.push(Move::CardMove{
-								card,
-								src: MoveSrc::FromFoundation(suit),
-								dest: MoveDest::ToPile(i),
-							});
-						}
-					}
-				}
-			}
-			MouseTarget::EmptyPile(_) => {} // impossible
-			MouseTarget::PileCard{pile_index, target_card:card, target_card_index, n_cards, ..} => {
-				let reveals_hidden_card = target_card_index == 0 && !self.piles[pile_index].hidden.is_empty();
-				if card.rank == Rank::Ace {
-					let dest = MoveDest::ToFoundation(card.suit);
-					moves.push(Move::CardMove{
-						card,
-						src: MoveSrc::FromPile{
-							pile_index,
-							n_cards,
-							progress_value: self.calc_progress_value_for_pile_card_move(pile_index, target_card_index, reveals_hidden_card, dest),
-							reveals_hidden_card,
-							target_card_index,
-						},
-						dest,
-					});
-				}
+// the number of cards fanned out on top of the waste
+fn n_fanned_waste_cards(game: &Game) -> usize {
+	return game.rules.draw_count.n_cards().min(game.waste.len())
+}
 
-				// consider moves to the foundation (iff it's a single card being targeted)
-				if n_cards == 1 {
-					for suit in Suit::all() {
-						if let Some(top) = self.foundation_top_card(*suit) {
-							let dest = MoveDest::ToFoundation(*suit);
-							if card.can_stack_onto_in_foundation(top) {
-								moves.push(Move::CardMove{
-									card,
-									src: MoveSrc::FromPile{
-										pile_index,
-										n_cards,
-										progress_value: self.calc_progress_value_for_pile_card_move(pile_index, target_card_index, reveals_hidden_card, dest),
-										reveals_hidden_card,
-										target_card_index,
-									},
-									dest,
-								});
-								break;
-							}
-						}
-					}
-				}
+// x-coord of left edge of the top card of the waste
+fn waste_top_x(game: &Game) -> f32 {
+	let n_fanned = n_fanned_waste_cards(game).max(1);
+	return WASTE_X + (n_fanned - 1) as f32 * WASTE_FAN_OFFSET
+}
 
-				// consider moves to other piles
-				for (i, pile) in self.piles[..].into_iter().enumerate() {
-					if i == pile_index { continue }
-
-					// if the card can go onto the top visible card, it's a valid move
-					if let Some(top) = pile.top_card() {
-						let dest = MoveDest::ToPile(i);
-						if card.can_pile_onto(top) {
-							moves.push(Move::CardMove{
-								card,
-								src: MoveSrc::FromPile{
-									pile_index,
-									n_cards,
-									progress_value: self.calc_progress_value_for_pile_card_move(pile_index, target_card_index, reveals_hidden_card, dest),
-									reveals_hidden_card,
-									target_card_index,
-								},
-								dest,
-							});
-						}
-					}
+fn cascade_layout() -> CascadeLayout {
+	let mut foundations = [(0., 0.); 4];
+	for suit in Suit::all() {
+		foundations[suit.foundation_index()] = (foundation_x(*suit), INSET);
+	}
+	return CascadeLayout { width: screen_width(), height: screen_height(), card_w: CARD_W, card_h: CARD_H, foundations }
+}
 
-					else if pile.is_empty() && card.rank == Rank::King {
-						// if the pile is empty and the card is a king, it's a valid move
-						let dest = MoveDest::ToPile(i);
-						moves.push(Move::CardMove{
-							card,
-							src: MoveSrc::FromPile{
-								pile_index,
-								n_cards,
-								progress_value: self.calc_progress_value_for_pile_card_move(pile_index, target_card_index, reveals_hidden_card, dest),
-								reveals_hidden_card,
-								target_card_index,
-							},
-							dest,
-						});
-					}
-				}
-			}
+// draws the cards that have left the foundations, each leaving a trail of copies behind it
+fn draw_cascade(cascade: &Cascade, textures:&HashMap<Suit, Texture2D>) {
+	for cc in cascade.cards() {
+		for (x, y) in cc.trail.iter() {
+			draw_card(&cc.card, *x, *y, true, textures);
 		}
-
-		if moves.is_empty() {
-			None
-		} else {
-			Some(moves)
+		if !cc.is_gone {
+			draw_card(&cc.card, cc.x, cc.y, true, textures);
 		}
 	}
+}
 
-	fn calc_progress_value_for_pile_card_move(&self, pile_index:usize, target_card_index:usize, reveals_hidden_card:bool, dest:MoveDest) -> usize {
-		if target_card_index == 0 {
-			if reveals_hidden_card { return 2 }
-
-			else if self.piles[pile_index].len() == 1 {
-				return match dest {
-					MoveDest::ToFoundation(_) => 1,
-					MoveDest::ToPile(dest_pile_index) => {
-						// if it's from a single-card pile to non-empty pile, that's progress.
-						if self.piles[dest_pile_index].len() > 0 {
-							1
-						} else { // otherwise it's just shifting between two empty piles.
-							0
-						}
-					}
-				}
-			}
-		}
-		
-		return 0
+fn draw_deal_number(game: &Game, deal_input: Option<&str>) {
+	let text = match (deal_input, game.seed) {
+		(Some(input), _) => format!("Deal #{}_", input),
+		(None, Some(seed)) => format!("Deal #{}", seed),
+		(None, None) => "Custom deal".to_string(),
+	};
+	draw_text(&text, STATUS_X, INSET + STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
+	if deal_input.is_some() {
+		draw_text("Enter to deal, Esc to cancel", STATUS_X, INSET + 2.*STATUS_LINE_H, STATUS_FONT_SIZE*0.75, BLACK);
+	} else {
+		let draw_text_str = format!("Draw {}", game.rules.draw_count.n_cards());
+		draw_text(&draw_text_str, STATUS_X, INSET + 2.*STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
+		let passes_text = match game.rules.pass_limit {
+			PassLimit::Unlimited => "Unlimited passes".to_string(),
+			PassLimit::Passes(1) => "1 pass".to_string(),
+			PassLimit::Passes(limit) => format!("{} passes", limit),
+		};
+		draw_text(&passes_text, STATUS_X, INSET + 3.*STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
 	}
+}
 
-	pub fn exec_move(&mut self, mv:Move) -> bool {
-		match mv {
-			Move::CardMove{ card, src, dest } => {
-				match src {
-					MoveSrc::FromStock => {
-						if let Some(card) = self.stock.front().cloned() {
-							self.stock.pop_front();
-							match dest {
-								MoveDest::ToPile(pile_index) => {
-									self.piles[pile_index].visible.push(card);
-								}
-								MoveDest::ToFoundation(suit) => {
-									self.foundation_fill_levels.insert(suit, card.rank);
-								}
-							}
-							// if there is a previous card, put it back at the front
-							if !self.stock.is_empty() {
-								if let Some(prev) = self.stock.pop_back() {
-									self.stock.push_front(prev)
-								}
-							}
-							self.move_history.push(mv);
-							return true
-						}
-					}
-					MoveSrc::FromFoundation(suit) => {
-						match dest {
-							MoveDest::ToPile(pile_index) => {
-								if let Some(top_card) = self.pop_foundation(suit) {
-									self.piles[pile_index].visible.push(top_card);
-									self.move_history.push(mv);
-									return true
-								}
-							}
-							MoveDest::ToFoundation(_) => {} // impossible
-						}
-					}
-					MoveSrc::FromPile{ pile_index, target_card_index, .. } => {
-						let pile = &mut self.piles[pile_index];
-						let removed:Vec<Card> = pile.visible.drain(target_card_index..).collect();
-						if pile.visible.is_empty() {
-							if let Some(next) = pile.hidden.pop() {
-								pile.visible.push(next);
-							}
-						}
-						match dest {
-							MoveDest::ToPile(dest_pile_index) => {
-								for card in removed {
-									self.piles[dest_pile_index].visible.push(card);
-								}
-							}
-							MoveDest::ToFoundation(suit) => {
-								self.foundation_fill_levels.insert(suit, card.rank);
-							}
-						}
-						self.move_history.push(mv);
-						return true
-					}
-				}
-			}
-			Move::CycleStock => {
-				// does nothing if the stock has 0 or 1 cards
-				if self.stock.len() > 1 {
-					let card = self.stock.pop_front().unwrap();
-					self.stock.push_back(card);
-					self.move_history.push(mv);
-					return true
-				}
-			}
-		}
-		return false
-	}
+// draws the strip along the bottom of the screen with the time, move count and score
+fn draw_hud(game: &Game) {
+	draw_hud_strip(&[
+		format!("Time: {}", time_text(game.elapsed)),
+		format!("Moves: {}", game.moves),
+		format!("Undos: {}", game.undos),
+		game.score_text(),
+	]);
+}
 
-	pub fn exec_move_in_progress(&mut self, target:MouseTarget) {
-		if let Some(mip) = &self.move_in_progress {
-			for mv in mip.moves.to_owned() {
-				match mv {
-					Move::CardMove { dest, .. } => {
-						match dest {
-							MoveDest::ToPile(mip_pile_index) => {
-								match target {
-									MouseTarget::StockTop => {} // impossible
-									MouseTarget::StockDeck => {} // impossible
-									MouseTarget::Foundation(_) => {} // not relevant for this move in progress
-									MouseTarget::EmptyPile(target_pile_index) => {
-										if mip_pile_index == target_pile_index {
-											self.exec_move(mv);
-											self.move_in_progress = None;
-											return
-										}
-									}
-									MouseTarget::PileCard{pile_index:target_pile_index, ..} => {
-										if mip_pile_index == target_pile_index {
-											self.exec_move(mv);
-											self.move_in_progress = None;
-											return
-										}
-									}
-								}
-							}
-							MoveDest::ToFoundation(mip_suit) => {
-								match target {
-									MouseTarget::StockTop => {} // impossible
-									MouseTarget::StockDeck => {} // impossible
-									MouseTarget::Foundation(target_suit) => {
-										if mip_suit == target_suit {
-											self.exec_move(mv);
-											self.move_in_progress = None;
-											return
-										}
-									}
-									MouseTarget::EmptyPile(_) => {} // not relevant for this move in progress
-									MouseTarget::PileCard{..} => {} // not relevant for this move in progress
-								}
-							}
-						}
-					}
-					Move::CycleStock => {
-						self.exec_move(mv);
-					}
-				}
-			}
-		}
-		// if we got to here, it means the move failed. Clear it
-		self.move_in_progress = None;
+fn draw_hud_strip(texts: &[String]) {
+	let y = screen_height() - HUD_H;
+	draw_rectangle(0., y, screen_width(), HUD_H, HUD_COLOUR);
+	let mut x = INSET;
+	for text in texts.iter() {
+		draw_text(text, x, y + HUD_H*0.7, STATUS_FONT_SIZE, WHITE);
+		x += measure_text(text, None, STATUS_FONT_SIZE as u16, 1.).width + INSET;
 	}
+}
 
-	// pops the top card off the given foundation.
-	// returns None if the foundation is empty.
-	pub fn pop_foundation(&mut self, suit:Suit) -> Option<Card> {
-		let top_card = self.foundation_top_card(suit)?;
-		if let Some(pred) = top_card.rank.pred() {
-			self.foundation_fill_levels.insert(suit, pred);
-		} else {
-			self.foundation_fill_levels.remove(&suit);
-		}
-		Some(top_card)
+fn draw_paused(while_away: bool) {
+	let cx = screen_width()*0.5;
+	let y = screen_height()*0.4;
+	draw_text_centred("Paused", cx, y, OVERLAY_TITLE_FONT_SIZE, BLACK);
+	if while_away {
+		draw_text_centred("The game stopped updating for a while (eg. the window was minimised), so the clock was paused", cx, y + STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
 	}
+	draw_text_centred("Press P or click to resume", cx, y + STATUS_LINE_H*2., STATUS_FONT_SIZE, BLACK);
+}
 
-	// if there are no moves to undo, does nothing
-	pub fn undo_move(&mut self) {
-		if let Some(mv) = self.move_history.pop() {
-			match mv {
-				Move::CardMove{ card:_, src, dest } => {
-					match src {
-						MoveSrc::FromStock => {
-							match dest {
-								MoveDest::ToPile(dest_pile_index) => {
-									if let Some(card) = self.piles[dest_pile_index].visible.pop() {
-										self.stock.push_front(card);
-									}
-								}
-								MoveDest::ToFoundation(suit) => {
-									if let Some(card) = self.pop_foundation(suit) {
-										self.stock.push_front(card);
-									}
-								}
-							}
-						}
-						MoveSrc::FromFoundation(suit) => {
-							match dest {
-								MoveDest::ToPile(dest_pile_index) => {
-									if let Some(card) = self.piles[dest_pile_index].visible.pop() {
-										self.foundation_fill_levels.insert(suit, card.rank);
-									}
-								}
-								MoveDest::ToFoundation(_) => {} // impossible
-							}
-						}
-						MoveSrc::FromPile{ pile_index, n_cards, reveals_hidden_card, .. } => {
-							match dest {
-								MoveDest::ToPile(dest_pile_index) => {
-									let dest_pile = &mut self.piles[dest_pile_index];
-									let index = dest_pile.visible.len() - n_cards as usize;
-									let removed:Vec<Card> = dest_pile.visible.drain(index..).collect();
-
-									let src_pile = &mut self.piles[pile_index];
-
-									// check if need to re-hide the prev hidden card
-									if reveals_hidden_card {
-										if let Some(card_to_rehide) = src_pile.visible.pop() {
-											src_pile.hidden.push(card_to_rehide);
-										}
-									}
-
-									for card in removed {
-										src_pile.visible.push(card);
-									}
-								}
-								MoveDest::ToFoundation(suit) => {
-									if let Some(card) = self.pop_foundation(suit) {
-										let src_pile = &mut self.piles[pile_index];
-
-										// check if need to re-hide the prev hidden card
-										if reveals_hidden_card {
-											if let Some(card_to_rehide) = src_pile.visible.pop() {
-												src_pile.hidden.push(card_to_rehide);
-											}
-										}
-
-										src_pile.visible.push(card);
-									}
-								}
-							}
-						}
-					}
-				}
-				Move::CycleStock => {
-					if self.stock.len() > 1 {
-						let card = self.stock.pop_back().unwrap();
-						self.stock.push_front(card);
-					}
-				}
-			}
-		}
+// draws a table of the statistics for every variant, with the current one highlighted
+fn draw_stats(stats: &Stats, rules: Rules, confirming_reset: bool) {
+	let cx = screen_width()*0.5;
+	let mut y = INSET + OVERLAY_TITLE_FONT_SIZE;
+	draw_text_centred("Statistics", cx, y, OVERLAY_TITLE_FONT_SIZE, BLACK);
+
+	let columns = ["", "Played", "Won", "Streak", "Best streak", "Fastest win", "Fewest moves", "Avg. score"];
+	let column_x = |i: usize| INSET + i as f32 * STATS_COLUMN_W + if i > 0 { STATS_COLUMN_W } else { 0. };
+	y += STATUS_LINE_H*2.;
+	for (i, heading) in columns.iter().enumerate() {
+		draw_text(heading, column_x(i), y, STATUS_FONT_SIZE, BLACK);
 	}
 
-	pub fn auto_move(&mut self) {
-		let mut moves = vec![Move::CycleStock];
-
-		// generate all possible moves
-		if let Some(mut stock_moves) = self.calc_moves(MouseTarget::StockTop) {
-			moves.append(&mut stock_moves);
-		}
-		for (pile_index, pile) in self.piles[..].into_iter().enumerate() {
-			for (card_index, card) in pile.visible[..].into_iter().enumerate() {
-				let target = MouseTarget::PileCard{
-					pile_index,
-					n_cards: (pile.visible.len() - card_index) as u8,
-					target_card: *card,
-					target_card_index: card_index,
-					top: 0., // doesn't matter for this purpose
-				};
-				if let Some(mut pile_moves) = self.calc_moves(target) {
-					moves.append(&mut pile_moves);
-				}
+	for draw_count in [DrawCount::One, DrawCount::Three] {
+		for scoring in [Scoring::Standard, Scoring::Vegas] {
+			y += STATUS_LINE_H;
+			let v = stats.variant(draw_count, scoring);
+			let scoring_name = match scoring {
+				Scoring::Standard => "Standard",
+				Scoring::Vegas => "Vegas",
+			};
+			let won = match v.win_rate() {
+				Some(rate) => format!("{} ({:.0}%)", v.won, rate*100.),
+				None => "-".to_string(),
+			};
+			let cells = [
+				format!("Draw {}, {}", draw_count.n_cards(), scoring_name),
+				v.played.to_string(),
+				won,
+				v.current_streak.to_string(),
+				v.best_streak.to_string(),
+				v.fastest_win.map_or("-".to_string(), time_text),
+				v.fewest_moves.map_or("-".to_string(), |moves| moves.to_string()),
+				v.average_score().map_or("-".to_string(), |score| format!("{:.0}", score)),
+			];
+			let is_current = rules.draw_count == draw_count && rules.scoring == scoring;
+			let col = if is_current { WHITE } else { BLACK };
+			for (i, cell) in cells.iter().enumerate() {
+				draw_text(cell, column_x(i), y, STATUS_FONT_SIZE, col);
 			}
 		}
+	}
 
-		// sort moves by quality
-		moves.sort_by(|a, b| {
-			match (a, b) {
-				// if both moves make progress, just pick one
-				(Move::CardMove{ src: MoveSrc::FromPile{ progress_value: pa, .. }, ..},
-				 Move::CardMove{ src: MoveSrc::FromPile{ progress_value: pb, .. }, ..}) if *pa > 0 || *pb > 0 => pb.cmp(&pa),
+	y += STATUS_LINE_H*2.;
+	let reset_text = if confirming_reset { "Press Delete again to reset all statistics" } else { "Press Delete to reset" };
+	draw_text_centred(reset_text, cx, y, STATUS_FONT_SIZE, BLACK);
+	draw_text_centred("Press I or click to close", cx, y + STATUS_LINE_H, STATUS_FONT_SIZE, BLACK);
+}
 
-				// if exactly one move makes progress, pick that one
-				(Move::CardMove{ src: MoveSrc::FromPile{ progress_value: p, .. }, ..}, _) if *p > 0 => Ordering::Less,
-				(_, Move::CardMove{ src: MoveSrc::FromPile{ progress_value: p, .. }, ..}) if *p > 0 => Ordering::Greater,
+#[derive(Copy, Clone, Debug)]
+enum GameOverChoice {
+	NewGame,
+	ReplayDeal,
+}
 
-				// if neither move makes progress, prefer moves to the foundation
-				(Move::CardMove{ dest: MoveDest::ToFoundation(_), .. }, _) => Ordering::Less,
-				(_, Move::CardMove{ dest: MoveDest::ToFoundation(_), .. }) => Ordering::Greater,
+// draws the end-of-game screen over the top of the board.
+// returns the player's choice if they clicked one of the buttons this frame.
+fn game_over_overlay(game: &Game, status: GameStatus) -> Option<GameOverChoice> {
+	draw_rectangle(0., 0., screen_width(), screen_height(), OVERLAY_COLOUR);
+
+	let title = match status {
+		GameStatus::Won => "You won!",
+		GameStatus::NoMovesRemaining => "No moves remaining",
+		GameStatus::InProgress => "",
+	};
+	let cx = screen_width()*0.5;
+	let mut y = screen_height()*0.3;
+	draw_text_centred(title, cx, y, OVERLAY_TITLE_FONT_SIZE, WHITE);
+
+	y += OVERLAY_TITLE_FONT_SIZE;
+	let summary = format!("Time: {}    Moves: {}    {}", time_text(game.elapsed), game.moves, game.score_text());
+	draw_text_centred(&summary, cx, y, STATUS_FONT_SIZE, WHITE);
+	if status == GameStatus::NoMovesRemaining {
+		y += STATUS_LINE_H;
+		draw_text_centred("Press U to undo", cx, y, STATUS_FONT_SIZE, WHITE);
+	}
 
-				// if there are no moves to the foundation, prefer moves from stock to piles
-				(Move::CardMove{ src: MoveSrc::FromStock, dest: MoveDest::ToPile(..), ..}, _) => Ordering::Less,
-				(_, Move::CardMove{ src: MoveSrc::FromStock, dest: MoveDest::ToPile(..), ..}) => Ordering::Greater,
+	y += STATUS_LINE_H;
+	let buttons = [
+		(GameOverChoice::NewGame, "New game (R)", cx - OVERLAY_BUTTON_W - INSET*0.5),
+		(GameOverChoice::ReplayDeal, "Replay deal (S)", cx + INSET*0.5),
+	];
+	let (mx, my) = mouse_position();
+	let mut choice = None;
+	for (button_choice, label, x) in buttons {
+		let hovered = mx >= x && mx <= x+OVERLAY_BUTTON_W && my >= y && my <= y+OVERLAY_BUTTON_H;
+		let col = if hovered { OVERLAY_BUTTON_HOVER_COLOUR } else { OVERLAY_BUTTON_COLOUR };
+		draw_rectangle(x, y, OVERLAY_BUTTON_W, OVERLAY_BUTTON_H, col);
+		draw_rectangle_lines(x, y, OVERLAY_BUTTON_W, OVERLAY_BUTTON_H, CARD_BORDER_WIDTH, BLACK);
+		draw_text_centred(label, x + OVERLAY_BUTTON_W*0.5, y + OVERLAY_BUTTON_H*0.65, STATUS_FONT_SIZE, BLACK);
+		if hovered && is_mouse_button_pressed(MouseButton::Left) {
+			choice = Some(button_choice);
+		}
+	}
+	return choice
+}
 
-				// just cycle the stock
-				(Move::CycleStock, _) => Ordering::Less,
-				(_, Move::CycleStock) => Ordering::Greater,
+// draws text horizontally centred on cx, with its baseline at y
+fn draw_text_centred(text: &str, cx:f32, y:f32, font_size:f32, col:Color) {
+	let dims = measure_text(text, None, font_size as u16, 1.);
+	draw_text(text, cx - dims.width*0.5, y, font_size, col);
+}
 
-				_ => Ordering::Equal,
-			}
-		});
+fn draw_foundation(suit: Suit, rank: Option<&Rank>, x:f32, y:f32, textures:&HashMap<Suit, Texture2D>) {
+	match rank {
+		Some(r) => {
+			draw_card(&Card::new(suit, *r), x, y, true, textures);
+		}
+		None => {
+			draw_rectangle_lines(x, y, CARD_W, CARD_H, CARD_BORDER_WIDTH, BLACK);
+			let tex = *textures.get(&suit).unwrap();
+			let col = Color::new(0., 0., 0., 0.7);
+			draw_texture_ex(tex, x+CARD_W*0.5-CARD_SUIT_TEX_SIZE_FOUNDATION*0.5, y+CARD_H*0.5-CARD_SUIT_TEX_SIZE_FOUNDATION*0.63, col, DrawTextureParams{
+				dest_size: Some(vec2(CARD_SUIT_TEX_SIZE_FOUNDATION, CARD_SUIT_TEX_SIZE_FOUNDATION)),
+				..Default::default()
+			});
+		}
+	}
+}
 
-		println!("moves: {:?}", moves);
+fn draw_pile(pile: &Pile, x:f32, y:f32, flying: &HashSet<Card>, textures:&HashMap<Suit, Texture2D>) {
+	if pile.hidden.iter().chain(pile.visible.iter()).all(|card| flying.contains(card)) {
+		draw_rectangle_lines(x, y, CARD_W, CARD_H, CARD_BORDER_WIDTH, BLACK);
+		return
+	}
 
-		// execute the first move in the list (ie. the highest quality move)
-		if let Some(mv) = moves.as_slice().first() {
-			self.exec_move(*mv);
+	for (i, card) in pile.hidden.iter().enumerate() {
+		if !flying.contains(card) {
+			draw_card(card, x, y + i as f32 * PILE_CARD_V_OFFSET, false, textures);
 		}
 	}
-
-	pub fn debug(&self) {
-		// TODO change this into a succinct Display, eg. print cards out as 2 chars
-		println!("stock: {:?}", self.stock);
-		println!("stock top: {:?}", self.stock.front());
-		println!("piles: {:?}", self.piles);
-		println!("foundations: {:?}", self.foundation_fill_levels);
-		println!("move_in_progress: {:?}", self.move_in_progress);
+	let n_hidden = pile.hidden.len() as f32;
+	for (i, card) in pile.visible.iter().enumerate() {
+		if !flying.contains(card) {
+			draw_card(card, x, y + (i as f32 + n_hidden) * PILE_CARD_V_OFFSET, true, textures);
+		}
 	}
 }
 
-#[derive(Copy, Clone, Debug)]
-enum MouseTarget {
-	StockTop, // the visible card of the stock
-	StockDeck, // the rest of the stock
-	Foundation(Suit), // one of the foundation piles
-	EmptyPile(usize), // an empty pile (valid target if moving a King to an empty space)
-	PileCard{ // a particular card in a pile
-		pile_index:usize, // 0 is the leftmost pile
-		n_cards:u8, // 1 = only the top card, 2 = two top cards, etc
-		target_card:Card, // the card that was targeted
-		target_card_index:usize, // the index into visible of the targeted card
-		top:f32, // the y-coord of the top of the targeted card
-	},
+fn draw_card(c: &Card, x:f32, y:f32, visible:bool, textures:&HashMap<Suit, Texture2D>) {
+	draw_card_w(c, x, y, CARD_W, visible, textures);
 }
 
-#[derive(Clone, Debug)]
-struct MoveInProgress {
-	target: MouseTarget,
-	moves: Vec<Move>,
-}
+// draws a card squashed horizontally to the given width, for when it's being flipped over
+fn draw_card_w(c: &Card, x:f32, y:f32, w:f32, visible:bool, textures:&HashMap<Suit, Texture2D>) {
+	let scale = w / CARD_W;
+	draw_rectangle(x, y, w, CARD_H, WHITE);
+	draw_rectangle_lines(x, y, w, CARD_H, CARD_BORDER_WIDTH, BLACK);
 
-#[derive(Copy, Clone, Debug)]
-enum Move {
-	CardMove{
-		card: Card,
-		src: MoveSrc,
-		dest: MoveDest,
-	},
-	CycleStock,
+	if visible {
+		let col = card_col(c);
+		draw_text_ex(c.rank.letter(), x, y + CARD_FONT_SIZE*0.55, TextParams{
+			font_size: CARD_FONT_SIZE as u16,
+			font_scale_aspect: scale,
+			color: col,
+			..Default::default()
+		});
+		draw_texture_ex(*textures.get(&c.suit).unwrap(), x+w*0.63, y+CARD_H*0.02, col, DrawTextureParams{
+			dest_size: Some(vec2(CARD_SUIT_TEX_SIZE_PILES*scale, CARD_SUIT_TEX_SIZE_PILES)),
+			..Default::default()
+		});
+	} else {
+		let margin = CARD_BACK_WHITE_BORDER_MARGIN.min(w*0.5);
+		draw_rectangle(
+			x+margin, y+CARD_BACK_WHITE_BORDER_MARGIN,
+			w-2.*margin, CARD_H-2.*CARD_BACK_WHITE_BORDER_MARGIN, CARD_BACK_COLOUR);
+	}
 }
 
-#[derive(Copy, Clone, Debug)]
-enum MoveSrc {
-	FromStock,
-	FromFoundation(Suit),
-	FromPile{
-		pile_index:usize, // 0 is the leftmost pile
-		n_cards:u8, // 1 = only the top card, 2 = two top cards, etc
-		progress_value:usize, // a heuristic of how much progress this move represents
-		reveals_hidden_card:bool,
-		target_card_index:usize, // the index into visible of the targeted card. Note: if this is
-								 // 0 and the hidden size > 0, that means the move uncovers a
-								 // hidden card
-	},
+// x-coord of left edge of the pile
+fn pile_x(pile_index:usize) -> f32 {
+	return INSET + pile_index as f32 * PILE_H_OFFSET;
 }
 
-#[derive(Copy, Clone, Debug)]
-enum MoveDest {
-	ToPile(usize),
-	ToFoundation(Suit),
+// y-coord of the top edge of the given visible card in a pile
+fn pile_card_y(pile: &Pile, card_index:usize) -> f32 {
+	return PILES_Y + (card_index + pile.hidden.len()) as f32 * PILE_CARD_V_OFFSET;
 }
 
-#[derive(Clone, Debug)]
-struct Pile {
-	hidden: Vec<Card>,
-	visible: Vec<Card>,
+// x-coord of left edge of the foundation for the given suit
+fn foundation_x(suit:Suit) -> f32 {
+	return FOUNDATIONS_X + suit.foundation_index() as f32 * PILE_H_OFFSET;
 }
 
-impl Pile {
-	pub fn new() -> Pile {
-		return Pile {
-			hidden: Vec::new(),
-			visible: Vec::new(),
-		};
+fn card_col(c: &Card) -> Color {
+	return match c.colour() {
+		Colour::Red => RED,
+		Colour::Black => BLACK,
 	}
+}
 
-	pub fn len(&self) -> usize {
-		self.hidden.len() + self.visible.len()
-	}
+fn card_mouse_hit(cx:f32, cy:f32, mx:f32, my:f32) -> bool {
+	return mx >= cx && mx <= cx+CARD_W
+		&& my >= cy && my <= cy+CARD_H
+}
 
-	// x-coord of left edge of the pile
-	pub fn pile_x(pile_index:usize) -> f32 {
-		return INSET + pile_index as f32 * PILE_H_OFFSET;
+fn mouse_hit(game: &Game, mx:f32, my:f32) -> Option<MouseTarget> {
+	// check stock and waste
+	if !game.waste.is_empty() && card_mouse_hit(waste_top_x(game), INSET, mx, my) {
+		return Some(MouseTarget::StockTop)
+	}
+	if card_mouse_hit(INSET, INSET, mx, my) {
+		if !game.stock.is_empty() {
+			return Some(MouseTarget::StockDeck)
+		} else if !game.waste.is_empty() {
+			return Some(MouseTarget::Recycle)
+		}
 	}
 
-	pub fn is_empty(&self) -> bool {
-		self.hidden.is_empty() && self.visible.is_empty()
+	// check foundations
+	for suit in Suit::all() {
+		if card_mouse_hit(foundation_x(*suit), INSET, mx, my) {
+			return Some(MouseTarget::Foundation(*suit))
+		}
 	}
 
-	pub fn top_card(&self) -> Option<Card> {
-		self.visible.as_slice().last().map(|card| *card)
+	// check piles
+	for (pile_index, pile) in game.piles.iter().enumerate() {
+		let x = pile_x(pile_index);
+		if mx < x || mx > x+CARD_W {continue}
+
+		// check visible cards in reverse order
+		for (card_index, card) in pile.visible.iter().enumerate().rev() {
+			let y = pile_card_y(pile, card_index);
+			if card_mouse_hit(x, y, mx, my) {
+				return Some(MouseTarget::PileCard{
+					pile_index,
+					n_cards: (pile.visible.len() - card_index) as u8,
+					target_card: *card,
+					target_card_index: card_index,
+					top: y,
+				})
+			}
+		}
+
+		if pile.is_empty() && card_mouse_hit(x, PILES_Y, mx, my) {
+			return Some(MouseTarget::EmptyPile(pile_index))
+		}
 	}
-}
 
-#[derive(Clone, Copy, Debug)]
-struct Card {
-	suit: Suit,
-	rank: Rank,
+	return None
 }
 
-impl Card {
-	pub fn new(suit: Suit, rank: Rank) -> Card {
-		return Card{suit, rank}
-	}
+// makes the move for a click on the given target. If it's ambiguous where the card should go, the
+// move is left in progress until the destination is clicked.
+fn click(game: &mut Game, target: MouseTarget) {
+	if Option::is_some(&game.move_in_progress) {
+		game.exec_move_in_progress(target);
+	} else {
+		game.move_in_progress = None;
+		if let Some(moves) = game.calc_moves(target) {
+			if moves.len() == 1 {
+				game.exec_move(moves[0]);
+			}
 
-	pub fn col(&self) -> Color {
-		return match self.suit {
-			Suit::Diamonds | Suit::Hearts => RED,
-			Suit::Clubs | Suit::Spades => BLACK,
+			// if there's a move to a foundation, do it
+			else if let mv@Move::CardMove{ dest: MoveDest::ToFoundation(..), .. } = moves[0] {
+				game.exec_move(mv);
+			}
+
+			else {
+				// if the only legal moves are from stock to piles, just pick one
+				let moves_all_from_stock_to_piles = moves.iter().all(|mv| {
+					matches!(mv, Move::CardMove{ src: MoveSrc::FromStock, dest: MoveDest::ToPile(..), .. })
+				});
+				if moves_all_from_stock_to_piles {
+					game.exec_move(moves[0]);
+				} else {
+					game.move_in_progress = Some(MoveInProgress{ target, moves });
+				}
+
+			}
 		}
 	}
+}
+
+// a message that's shown at the bottom of the screen for a few seconds
+struct Notice {
+	text: String,
+	expires_at: f64,
+}
 
-	// Returns an array slice containing all the cards in a standard 52-card deck
-	pub fn all_cards() -> &'static [Card] {
-		static CARDS: [Card; 52] = [
-			Card { suit: Suit::Spades, rank: Rank::Two },
-			Card { suit: Suit::Spades, rank: Rank::Three },
-			Card { suit: Suit::Spades, rank: Rank::Four },
-			Card { suit: Suit::Spades, rank: Rank::Five },
-			Card { suit: Suit::Spades, rank: Rank::Six },
-			Card { suit: Suit::Spades, rank: Rank::Seven },
-			Card { suit: Suit::Spades, rank: Rank::Eight },
-			Card { suit: Suit::Spades, rank: Rank::Nine },
-			Card { suit: Suit::Spades, rank: Rank::Ten },
-			Card { suit: Suit::Spades, rank: Rank::Jack },
-			Card { suit: Suit::Spades, rank: Rank::Queen },
-			Card { suit: Suit::Spades, rank: Rank::King },
-			Card { suit: Suit::Spades, rank: Rank::Ace },
-			Card { suit: Suit::Hearts, rank: Rank::Two },
-			Card { suit: Suit::Hearts, rank: Rank::Three },
-			Card { suit: Suit::Hearts, rank: Rank::Four },
-			Card { suit: Suit::Hearts, rank: Rank::Five },
-			Card { suit: Suit::Hearts, rank: Rank::Six },
-			Card { suit: Suit::Hearts, rank: Rank::Seven },
-			Card { suit: Suit::Hearts, rank: Rank::Eight },
-			Card { suit: Suit::Hearts, rank: Rank::Nine },
-			Card { suit: Suit::Hearts, rank: Rank::Ten },
-			Card { suit: Suit::Hearts, rank: Rank::Jack },
-			Card { suit: Suit::Hearts, rank: Rank::Queen },
-			Card { suit: Suit::Hearts, rank: Rank::King },
-			Card { suit: Suit::Hearts, rank: Rank::Ace },
-			Card { suit: Suit::Diamonds, rank: Rank::Two },
-			Card { suit: Suit::Diamonds, rank: Rank::Three },
-			Card { suit: Suit::Diamonds, rank: Rank::Four },
-			Card { suit: Suit::Diamonds, rank: Rank::Five },
-			Card { suit: Suit::Diamonds, rank: Rank::Six },
-			Card { suit: Suit::Diamonds, rank: Rank::Seven },
-			Card { suit: Suit::Diamonds, rank: Rank::Eight },
-			Card { suit: Suit::Diamonds, rank: Rank::Nine },
-			Card { suit: Suit::Diamonds, rank: Rank::Ten },
-			Card { suit: Suit::Diamonds, rank: Rank::Jack },
-			Card { suit: Suit::Diamonds, rank: Rank::Queen },
-			Card { suit: Suit::Diamonds, rank: Rank::King },
-			Card { suit: Suit::Diamonds, rank: Rank::Ace },
-			Card { suit: Suit::Clubs, rank: Rank::Two },
-			Card { suit: Suit::Clubs, rank: Rank::Three },
-			Card { suit: Suit::Clubs, rank: Rank::Four },
-			Card { suit: Suit::Clubs, rank: Rank::Five },
-			Card { suit: Suit::Clubs, rank: Rank::Six },
-			Card { suit: Suit::Clubs, rank: Rank::Seven },
-			Card { suit: Suit::Clubs, rank: Rank::Eight },
-			Card { suit: Suit::Clubs, rank: Rank::Nine },
-			Card { suit: Suit::Clubs, rank: Rank::Ten },
-			Card { suit: Suit::Clubs, rank: Rank::Jack },
-			Card { suit: Suit::Clubs, rank: Rank::Queen },
-			Card { suit: Suit::Clubs, rank: Rank::King },
-			Card { suit: Suit::Clubs, rank: Rank::Ace }
-		];
-		&CARDS
+impl Notice {
+	fn new(text: String) -> Notice {
+		return Notice { text, expires_at: get_time() + NOTICE_DURATION }
 	}
 
-	pub fn mouse_hit(cx:f32, cy:f32, mx:f32, my:f32) -> bool {
-		return mx >= cx && mx <= cx+CARD_W
-			&& my >= cy && my <= cy+CARD_H
+	fn has_expired(&self) -> bool {
+		return get_time() > self.expires_at
 	}
+}
 
-	// returns true if self can stack on top of other in a pile, eg. if self is 2D and other is 3S.
-	pub fn can_pile_onto(&self, other:Card) -> bool {
-		self.col() != other.col() && other.rank.index() - self.rank.index() == 1
+// the save file lives in the user's data directory, eg. ~/.local/share on linux
+fn save_path() -> Option<PathBuf> {
+	return dirs::data_dir().map(|dir| dir.join("solitaire-macroquad").join("save.json"))
+}
+
+fn save_game(game: &Game) {
+	if let Some(path) = save_path() {
+		if let Err(err) = game.save(&path) {
+			eprintln!("Couldn't save the game to {}: {}", path.display(), err);
+		}
 	}
+}
+
+// returns Ok(None) if there's no saved game to load
+fn load_saved_game() -> Result<Option<Game>, SaveError> {
+	let path = match save_path() {
+		Some(path) if path.exists() => path,
+		_ => return Ok(None),
+	};
+	return Game::load(&path).map(Some)
+}
+
+// the stats are kept next to the save file
+fn stats_path() -> Option<PathBuf> {
+	return dirs::data_dir().map(|dir| dir.join("solitaire-macroquad").join("stats.json"))
+}
 
-	// returns true if self can stack on top of other in a foundation stack, eg. if self is 2D and other is AD.
-	pub fn can_stack_onto_in_foundation(&self, other:Card) -> bool {
-		self.suit == other.suit && self.rank.index() - other.rank.index() == 1
+fn save_stats(stats: &Stats) {
+	if let Some(path) = stats_path() {
+		if let Err(err) = stats.save(&path) {
+			eprintln!("Couldn't save the statistics to {}: {}", path.display(), err);
+		}
 	}
 }
 
-fn shuffle(cards: &mut[Card]) {
-	let l = cards.len();
-	for n in 0..l {
-		let i = rand::gen_range(0, l - n);
-		cards.swap(i, l - n - 1);
+// returns empty stats if none have been saved yet
+fn load_stats() -> Result<Stats, SaveError> {
+	return match stats_path() {
+		Some(path) if path.exists() => Stats::load(&path),
+		_ => Ok(Stats::default()),
 	}
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Copy)]
-enum Suit {
-	Diamonds,
-	Clubs,
-	Hearts,
-	Spades,
+// replays are kept in their own directory next to the save file
+fn replays_dir() -> Option<PathBuf> {
+	return dirs::data_dir().map(|dir| dir.join("solitaire-macroquad").join("replays"))
 }
 
-impl Suit {
-	pub fn all() -> &'static [Suit] {
-		static SUITS: [Suit; 4] = [
-			Suit::Diamonds,
-			Suit::Clubs,
-			Suit::Hearts,
-			Suit::Spades,
-		];
-		&SUITS
+// writes a replay of a won (or abandoned) game, named after its deal number and when it ended.
+// does nothing if there's no replay to save yet (see Replay::to_save).
+fn save_replay(game: &mut Game, abandoned: bool) {
+	let (replay, dir) = match (Replay::to_save(game, abandoned), replays_dir()) {
+		(Some(replay), Some(dir)) => (replay, dir),
+		_ => return,
+	};
+	let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+	let path = dir.join(format!("{}-{}.json", now, replay.seed));
+	if let Err(err) = replay.save(&path) {
+		eprintln!("Couldn't save the replay to {}: {}", path.display(), err);
 	}
+}
 
-	pub fn foundation_offset(&self) -> f32 {
-		return match self {
-			Suit::Diamonds => 0.,
-			Suit::Clubs => 1.,
-			Suit::Hearts => 2.,
-			Suit::Spades => 3.
+// returns the value following the given flag on the command line, eg. `--replay path`
+fn arg_value(flag: &str) -> Option<String> {
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		if arg == flag {
+			return args.next()
 		}
 	}
+	return None
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd)]
-enum Rank {
-	Ace,
-	Two,
-	Three,
-	Four,
-	Five,
-	Six,
-	Seven,
-	Eight,
-	Nine,
-	Ten,
-	Jack,
-	Queen,
-	King,
+// redo is Shift+U or Ctrl+Y
+fn is_redo_pressed() -> bool {
+	let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+	let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+	return (shift && is_key_pressed(KeyCode::U)) || (ctrl && is_key_pressed(KeyCode::Y))
 }
 
-impl Rank {
-	pub fn from_index(i: i8) -> Option<Rank> {
-		return match i {
-			0 => Some(Rank::Ace),
-			1 => Some(Rank::Two),
-			2 => Some(Rank::Three),
-			3 => Some(Rank::Four),
-			4 => Some(Rank::Five),
-			5 => Some(Rank::Six),
-			6 => Some(Rank::Seven),
-			7 => Some(Rank::Eight),
-			8 => Some(Rank::Nine),
-			9 => Some(Rank::Ten),
-			10 => Some(Rank::Jack),
-			11 => Some(Rank::Queen),
-			12 => Some(Rank::King),
-			_ => None
-		}
-	}
+// deals a game with a random deal number
+fn new_game(rules: Rules) -> Game {
+	Game::from_seed(rand::rand() as u64, rules)
+}
 
-	pub fn letter(&self) -> &str {
-		return match self {
-			Rank::Ace => "A",
-			Rank::Two => "2",
-			Rank::Three => "3",
-			Rank::Four => "4",
-			Rank::Five => "5",
-			Rank::Six => "6",
-			Rank::Seven => "7",
-			Rank::Eight => "8",
-			Rank::Nine => "9",
-			Rank::Ten => "10",
-			Rank::Jack => "J",
-			Rank::Queen => "Q",
-			Rank::King => "K",
+// input that changes the game, which can be queued up while cards are moving
+#[derive(Copy, Clone, Debug)]
+enum Action {
+	Click(Vec2), // a left click at the given mouse position
+	DrawStock,
+	Undo,
+	Redo,
+	AutoMove,
+}
+
+fn apply_action(game: &mut Game, action: Action) {
+	match action {
+		Action::Click(pos) => {
+			if game.status() == GameStatus::InProgress {
+				if let Some(target) = mouse_hit(game, pos.x, pos.y) {
+					click(game, target);
+				}
+			}
 		}
+		Action::DrawStock => {
+			let moves = game.calc_moves(MouseTarget::StockDeck).or_else(|| game.calc_moves(MouseTarget::Recycle));
+			if let Some(moves) = moves {
+				game.exec_move(moves[0]);
+			}
+		}
+		Action::Undo => game.undo_move(),
+		Action::Redo => { game.redo_move(); }
+		Action::AutoMove => { game.auto_move(); }
 	}
+}
 
-	pub fn index(&self) -> i8 {
-		return match self {
-			Rank::Ace => 0,
-			Rank::Two => 1,
-			Rank::Three => 2,
-			Rank::Four => 3,
-			Rank::Five => 4,
-			Rank::Six => 5,
-			Rank::Seven => 6,
-			Rank::Eight => 7,
-			Rank::Nine => 8,
-			Rank::Ten => 9,
-			Rank::Jack => 10,
-			Rank::Queen => 11,
-			Rank::King => 12,
-		}
+// lands every card immediately and carries out all the queued input
+fn skip_animations(game: &mut Game, animator: &mut Animator, queued_actions: &mut VecDeque<Action>) {
+	while let Some(action) = queued_actions.pop_front() {
+		apply_action(game, action);
 	}
+	animator.sync(game);
+	animator.skip();
+}
 
-	// returns the predecessor of the current rank.
-	// eg. pred(King) == Some(Queen), pred(Ace) = None.
-	pub fn pred(&self) -> Option<Rank> {
-		let prev_index = self.index() - 1;
-		Rank::from_index(prev_index)
+fn animation_speed_text(animator: &Animator) -> String {
+	if animator.speed() == 0. {
+		return "Animations off".to_string()
 	}
+	return format!("Animation speed {}x", animator.speed())
 }
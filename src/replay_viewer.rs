@@ -0,0 +1,93 @@
+// The replay viewer, for watching a recorded game.
+//
+// Moves are played back with the same gaps between them as when the game was played (sped up or
+// slowed down by the playback speed), except that long pauses are cut short.
+
+use crate::animation::Animator;
+use crate::{draw_game, draw_hud_strip, draw_text_centred, BG_COLOUR, HUD_H, STATUS_FONT_SIZE, STATUS_LINE_H};
+use macroquad::prelude::*;
+use solitaire_core::{time_text, Replay, Replayer, Suit};
+use std::collections::HashMap;
+
+const SPEEDS: [f64; 5] = [0.5, 1., 2., 4., 8.];
+const DEFAULT_SPEED_INDEX: usize = 1;
+const MAX_GAP: f64 = 2.; // seconds. the longest wait between moves at 1x speed
+
+pub async fn run(replay: Replay, textures: &HashMap<Suit, Texture2D>) {
+	let mut replayer = match Replayer::new(replay) {
+		Ok(replayer) => replayer,
+		Err(err) => {
+			eprintln!("Couldn't play the replay: {}", err);
+			return
+		}
+	};
+	let mut animator = Animator::new(replayer.game());
+	animator.deal(replayer.game());
+
+	let mut playing = true;
+	let mut speed_index = DEFAULT_SPEED_INDEX;
+	let mut waited = 0.; // seconds (at 1x speed) since the last move was made
+
+	loop {
+		clear_background(BG_COLOUR);
+
+		if is_quit_requested() || is_key_pressed(KeyCode::Escape) {
+			return
+		}
+
+		if is_key_pressed(KeyCode::Space) {
+			// playing from the end starts again from the beginning
+			if !playing && replayer.is_at_end() {
+				replayer.seek(0);
+			}
+			playing = !playing;
+			waited = 0.;
+		} else if is_key_pressed(KeyCode::Right) {
+			playing = false;
+			replayer.step_forward();
+		} else if is_key_pressed(KeyCode::Left) {
+			playing = false;
+			replayer.step_back();
+		} else if is_key_pressed(KeyCode::Home) {
+			playing = false;
+			replayer.seek(0);
+		} else if is_key_pressed(KeyCode::End) {
+			playing = false;
+			replayer.seek(usize::MAX);
+		} else if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+			speed_index = speed_index.saturating_sub(1);
+		} else if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+			speed_index = (speed_index + 1).min(SPEEDS.len() - 1);
+		}
+
+		animator.update(get_frame_time());
+		if playing && !animator.is_busy() {
+			waited += get_frame_time() as f64 * SPEEDS[speed_index];
+			match replayer.time_to_next_move() {
+				Some(gap) if waited >= gap.min(MAX_GAP) => {
+					replayer.step_forward();
+					waited = 0.;
+				}
+				Some(_) => {}
+				None => playing = false,
+			}
+		}
+		animator.sync(replayer.game());
+
+		let game = replayer.game();
+		draw_game(game, &animator.flying_cards(), textures);
+		animator.draw(textures);
+
+		let state = if playing { format!("Playing at {}x", SPEEDS[speed_index]) } else { "Paused".to_string() };
+		draw_hud_strip(&[
+			format!("Replay of deal #{}", replayer.replay().seed),
+			format!("Move {}/{}", replayer.position(), replayer.replay().moves.len()),
+			format!("Time: {}", time_text(game.elapsed)),
+			state,
+		]);
+		let help = "Space: play/pause    Left/Right: step    Home/End: start/end    -/=: speed    Esc: quit";
+		draw_text_centred(help, screen_width()*0.5, screen_height() - HUD_H - STATUS_LINE_H*0.5, STATUS_FONT_SIZE, BLACK);
+
+		next_frame().await;
+	}
+}